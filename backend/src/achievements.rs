@@ -0,0 +1,183 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{Enum, InputObject, SimpleObject};
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Difficulty;
+
+/// A badge that owners earn once its rule is met when one of their solutions is accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "AchievementInput")]
+pub struct Achievement {
+    /// The unique name of the achievement.
+    pub name: String,
+    /// What the owner must do to earn the achievement.
+    pub description: String,
+    /// The rule deciding when the achievement is earned.
+    pub rule: AchievementRule,
+}
+
+/// The kind of an achievement rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum AchievementKind {
+    /// Solved every registered puzzle of the difficulty, or every registered puzzle if
+    /// there is no difficulty.
+    SolvedAll,
+    /// Solved at least `count` puzzles on the same day (UTC).
+    SolvedInOneDay,
+    /// Solved at least `count` puzzles of the difficulty, or of any difficulty.
+    SolvedCount,
+    /// Was the first solver of a puzzle of the difficulty, or of any difficulty.
+    FirstSolver,
+    /// Solved a puzzle with a quality ranking at par or better.
+    MeetsPar,
+}
+
+/// A declarative rule of an achievement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "AchievementRuleInput")]
+pub struct AchievementRule {
+    /// The kind of the rule.
+    pub kind: AchievementKind,
+    /// The difficulty of the puzzles taken into account, if any.
+    pub difficulty: Option<Difficulty>,
+    /// The number of puzzles, for the kinds that count puzzles.
+    pub count: u32,
+}
+
+/// The number of puzzles of each difficulty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DifficultyCounts(pub Vec<(Difficulty, u32)>);
+
+impl DifficultyCounts {
+    /// Counts one more puzzle of the difficulty.
+    pub fn increment(&mut self, difficulty: Difficulty) {
+        match self.0.iter_mut().find(|(d, _)| *d == difficulty) {
+            Some((_, count)) => *count += 1,
+            None => self.0.push((difficulty, 1)),
+        }
+    }
+
+    /// The number of puzzles of the difficulty, or of any difficulty.
+    pub fn count(&self, difficulty: Option<Difficulty>) -> u32 {
+        self.0
+            .iter()
+            .filter(|(d, _)| difficulty.is_none_or(|difficulty| difficulty == *d))
+            .map(|(_, count)| *count)
+            .sum()
+    }
+}
+
+/// What a scoring chain knows about an owner when one of their solutions to a registered
+/// puzzle is accepted, whether it is credited or was already.
+#[derive(Debug, Clone)]
+pub struct SolverFacts {
+    /// The number of registered puzzles credited to the owner.
+    pub solved_counts: DifficultyCounts,
+    /// The number of registered puzzles credited to the owner for solutions of the same day
+    /// (UTC) as the credited solution of the puzzle.
+    pub daily_solved_counts: DifficultyCounts,
+    /// The number of registered puzzles.
+    pub registered_counts: DifficultyCounts,
+    /// The difficulty of the puzzle.
+    pub difficulty: Difficulty,
+    /// The rank of the owner among the solvers of the puzzle.
+    pub rank: u32,
+    /// Whether the accepted solution meets the par value of the puzzle, if it has a quality
+    /// ranking.
+    pub meets_par: Option<bool>,
+}
+
+impl AchievementRule {
+    /// Whether the rule is met by the facts of an accepted solution.
+    pub fn is_met(&self, facts: &SolverFacts) -> bool {
+        let matches = |difficulty: &Difficulty| self.difficulty.is_none_or(|d| d == *difficulty);
+        match self.kind {
+            AchievementKind::SolvedAll => {
+                let registered_count = facts.registered_counts.count(self.difficulty);
+                registered_count > 0
+                    && facts.solved_counts.count(self.difficulty) >= registered_count
+            }
+            AchievementKind::SolvedInOneDay => {
+                facts.daily_solved_counts.count(self.difficulty) >= self.count
+            }
+            AchievementKind::SolvedCount => {
+                facts.solved_counts.count(self.difficulty) >= self.count
+            }
+            AchievementKind::FirstSolver => facts.rank == 1 && matches(&facts.difficulty),
+            AchievementKind::MeetsPar => {
+                facts.meets_par == Some(true) && matches(&facts.difficulty)
+            }
+        }
+    }
+}
+
+/// An achievement earned by an owner.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct Badge {
+    /// The name of the achievement.
+    pub name: String,
+    /// What the owner did to earn the achievement.
+    pub description: String,
+    /// When the achievement was earned.
+    pub earned_at: Timestamp,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: AchievementKind, difficulty: Option<Difficulty>, count: u32) -> AchievementRule {
+        AchievementRule {
+            kind,
+            difficulty,
+            count,
+        }
+    }
+
+    fn counts(difficulties: &[Difficulty]) -> DifficultyCounts {
+        let mut counts = DifficultyCounts::default();
+        for difficulty in difficulties {
+            counts.increment(*difficulty);
+        }
+        counts
+    }
+
+    #[test]
+    fn test_difficulty_counts() {
+        let counts = counts(&[Difficulty::Easy, Difficulty::Hard, Difficulty::Easy]);
+        assert_eq!(counts.count(Some(Difficulty::Easy)), 2);
+        assert_eq!(counts.count(Some(Difficulty::Hard)), 1);
+        assert_eq!(counts.count(Some(Difficulty::Expert)), 0);
+        assert_eq!(counts.count(None), 3);
+    }
+
+    #[test]
+    fn test_achievement_rules() {
+        use Difficulty::*;
+        // A tutorial puzzle solved the day before, then an expert and a tutorial puzzle.
+        let facts = SolverFacts {
+            solved_counts: counts(&[Tutorial, Expert, Tutorial]),
+            daily_solved_counts: counts(&[Expert, Tutorial]),
+            registered_counts: counts(&[Tutorial, Tutorial, Easy, Expert]),
+            difficulty: Tutorial,
+            rank: 1,
+            meets_par: None,
+        };
+        use AchievementKind::*;
+        assert!(rule(SolvedAll, Some(Difficulty::Tutorial), 0).is_met(&facts));
+        assert!(rule(SolvedAll, Some(Difficulty::Expert), 0).is_met(&facts));
+        assert!(!rule(SolvedAll, Some(Difficulty::Easy), 0).is_met(&facts));
+        assert!(!rule(SolvedAll, Some(Difficulty::Hard), 0).is_met(&facts));
+        assert!(!rule(SolvedAll, None, 0).is_met(&facts));
+        assert!(rule(SolvedInOneDay, None, 2).is_met(&facts));
+        assert!(!rule(SolvedInOneDay, None, 3).is_met(&facts));
+        assert!(rule(SolvedCount, None, 3).is_met(&facts));
+        assert!(!rule(SolvedCount, Some(Difficulty::Tutorial), 3).is_met(&facts));
+        assert!(rule(FirstSolver, Some(Difficulty::Tutorial), 0).is_met(&facts));
+        assert!(!rule(FirstSolver, Some(Difficulty::Expert), 0).is_met(&facts));
+        assert!(!rule(MeetsPar, None, 0).is_met(&facts));
+    }
+}
@@ -4,11 +4,22 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 #![cfg(not(target_arch = "wasm32"))]
 
-use std::{collections::HashMap, fs, io::Write, path::PathBuf};
-
-use async_graphql::InputType as _;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs,
+    io::Write,
+    path::PathBuf,
+};
+
+use async_graphql::{InputType as _, Name, Value};
 use clap::{Parser, Subcommand};
-use gol_challenge::game::{Board, Condition, Difficulty, Position, Puzzle};
+use gol_challenge::{
+    campaign::{validate_campaign, CampaignPuzzle},
+    game::{Board, Condition, Difficulty, Position, Puzzle},
+    quality::{QualityMetric, QualityRanking},
+    templates::{PuzzleBlob, PuzzleTemplate, RankedPuzzle},
+};
 use linera_sdk::linera_base_types::{AccountOwner, ChainId, DataBlobHash};
 
 #[derive(Parser)]
@@ -48,6 +59,39 @@ enum Commands {
         #[arg(long)]
         name: Option<String>,
     },
+    /// Create a puzzle template from a puzzle file. The template has a different instance
+    /// for each owner, with the conditions of the puzzle translated and possibly mirrored
+    CreateTemplate {
+        /// Path to the puzzle file
+        puzzle: PathBuf,
+        /// Output file path for the template
+        #[arg(short, long)]
+        output: PathBuf,
+        /// The maximal translation of the conditions along the x-axis
+        #[arg(long, default_value_t = 0)]
+        max_offset_x: u16,
+        /// The maximal translation of the conditions along the y-axis
+        #[arg(long, default_value_t = 0)]
+        max_offset_y: u16,
+        /// Whether instances may be mirrored along each axis
+        #[arg(long)]
+        allow_mirroring: bool,
+    },
+    /// Declare the quality ranking of the solvers of a puzzle or template file. Scoring
+    /// chains rank the solvers by their best solution
+    RankPuzzle {
+        /// Path to the puzzle or template file
+        puzzle: PathBuf,
+        /// Output file path for the ranked puzzle
+        #[arg(short, long)]
+        output: PathBuf,
+        /// The metric to rank solutions on: LIVE_CELLS, BOUNDING_BOX_AREA, STEPS or SYMMETRY
+        #[arg(long)]
+        metric: String,
+        /// The value of the metric expected from a good solution
+        #[arg(long)]
+        par: Option<u32>,
+    },
     /// Print the contents of a puzzle file
     PrintPuzzle {
         /// Path to the puzzle file to print
@@ -73,10 +117,12 @@ enum Commands {
         /// Path to JSON file mapping puzzle names to blob IDs
         #[arg(long)]
         blob_map: PathBuf,
-        /// Scoring chain ID to use in the mutation
-        #[arg(long)]
-        scoring_chain_id: ChainId,
-        /// Optional account owner to credit for the solutions
+        /// Scoring chain IDs to use in the mutation (may be repeated)
+        #[arg(long = "scoring-chain-id")]
+        scoring_chain_ids: Vec<ChainId>,
+        /// Optional account owner to credit for the solutions. Unless the owner signs the
+        /// transaction, each solution requires their consent, passed as the variables
+        /// `$consent0`, `$consent1`, etc. of the mutation
         #[arg(long)]
         owner: Option<AccountOwner>,
         /// Include inactive puzzles as well
@@ -86,6 +132,21 @@ enum Commands {
         #[arg(long)]
         name: Option<String>,
     },
+    /// Generate a GraphQL mutation creating a campaign from the puzzle catalog
+    GenerateCampaignMutation {
+        /// Path to JSON file mapping puzzle names to blob IDs
+        #[arg(long)]
+        blob_map: PathBuf,
+        /// The name of the campaign
+        #[arg(long)]
+        campaign: String,
+        /// Include inactive puzzles as well
+        #[arg(long)]
+        all: bool,
+        /// Only include puzzles containing the given string in their name
+        #[arg(long)]
+        name: Option<String>,
+    },
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -108,6 +169,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         } => {
             generate_metadata(&output, &blob_map, all, name.as_deref())?;
         }
+        Commands::CreateTemplate {
+            puzzle,
+            output,
+            max_offset_x,
+            max_offset_y,
+            allow_mirroring,
+        } => {
+            create_template(
+                &puzzle,
+                &output,
+                max_offset_x,
+                max_offset_y,
+                allow_mirroring,
+            )?;
+        }
+        Commands::RankPuzzle {
+            puzzle,
+            output,
+            metric,
+            par,
+        } => {
+            rank_puzzle(&puzzle, &output, &metric, par)?;
+        }
         Commands::PrintPuzzle { path } => {
             print_puzzle(&path)?;
         }
@@ -119,12 +203,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         Commands::GenerateSubmitMutation {
             blob_map,
-            scoring_chain_id,
+            scoring_chain_ids,
             owner,
             all,
             name,
         } => {
-            generate_submit_mutation(&blob_map, scoring_chain_id, owner, all, name.as_deref())?;
+            generate_submit_mutation(&blob_map, scoring_chain_ids, owner, all, name.as_deref())?;
+        }
+        Commands::GenerateCampaignMutation {
+            blob_map,
+            campaign,
+            all,
+            name,
+        } => {
+            generate_campaign_mutation(&blob_map, &campaign, all, name.as_deref())?;
         }
     }
 
@@ -375,6 +467,7 @@ fn create_puzzles(
 
     for (name, puzzle_and_solution_creator) in puzzles {
         let (mut puzzle, solution) = puzzle_and_solution_creator();
+        puzzle.validate()?;
 
         let puzzle_path = output_dir.join(format!("{}_puzzle.bcs", name));
         let solution_path = output_dir.join(format!("{}_solution.bcs", name));
@@ -1448,6 +1541,50 @@ fn create_high_density_puzzle_and_solution() -> (Puzzle, Board) {
     (puzzle, initial_board)
 }
 
+fn create_template(
+    puzzle_path: &PathBuf,
+    output: &PathBuf,
+    max_offset_x: u16,
+    max_offset_y: u16,
+    allow_mirroring: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let puzzle_bytes = fs::read(puzzle_path)?;
+    let puzzle: Puzzle = bcs::from_bytes(&puzzle_bytes)?;
+    let template = PuzzleTemplate {
+        puzzle,
+        max_offset_x,
+        max_offset_y,
+        allow_mirroring,
+    };
+    template.validate()?;
+    fs::write(output, template.to_bytes()?)?;
+    println!("Created template: {}", output.display());
+    Ok(())
+}
+
+fn rank_puzzle(
+    puzzle_path: &PathBuf,
+    output: &PathBuf,
+    metric: &str,
+    par: Option<u32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let metric = QualityMetric::parse(Some(Value::Enum(Name::new(metric))))
+        .map_err(|_| format!("Invalid metric: {metric}"))?;
+    let blob = fs::read(puzzle_path)?;
+    let puzzle_blob = PuzzleBlob::from_bytes(&blob)?;
+    if puzzle_blob.ranking().is_some() {
+        return Err("The puzzle is already ranked".into());
+    }
+    puzzle_blob.validate()?;
+    let ranked_puzzle = RankedPuzzle {
+        ranking: QualityRanking { metric, par },
+        blob,
+    };
+    fs::write(output, ranked_puzzle.to_bytes()?)?;
+    println!("Created ranked puzzle: {}", output.display());
+    Ok(())
+}
+
 fn print_puzzle(path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     let puzzle_bytes = fs::read(path)?;
     let puzzle: Puzzle = bcs::from_bytes(&puzzle_bytes)?;
@@ -1500,7 +1637,7 @@ fn check_solution(
 
 fn generate_submit_mutation(
     blob_map_path: &PathBuf,
-    scoring_chain_id: ChainId,
+    scoring_chain_ids: Vec<ChainId>,
     owner: Option<AccountOwner>,
     all: bool,
     name: Option<&str>,
@@ -1521,8 +1658,9 @@ fn generate_submit_mutation(
         }
     };
 
-    // Start building the mutation
-    println!("mutation {{");
+    // Build the body of the mutation, then declare the consent variables, if any.
+    let mut body = String::new();
+    let mut variables = Vec::new();
 
     for (index, (name, puzzle_creator)) in puzzles_info.iter().enumerate() {
         let (_, solution_board) = puzzle_creator();
@@ -1561,20 +1699,122 @@ fn generate_submit_mutation(
         let alias = format!("puzzle{}", index);
 
         // Write the submitSolution mutation block
-        println!("  {}: submitSolution(", alias);
-        println!("    puzzleId: {}", puzzle_id.to_value());
-        println!(
+        writeln!(body, "  {}: submitSolution(", alias)?;
+        writeln!(body, "    puzzleId: {}", puzzle_id.to_value())?;
+        writeln!(
+            body,
             "    board: {{ size: {}, liveCells: [{}] }}",
             size, live_cells
-        );
+        )?;
         if let Some(owner) = owner {
-            println!("    owner: {}", owner.to_value());
+            let variable = format!("$consent{}", index);
+            writeln!(body, "    owner: {}", owner.to_value())?;
+            writeln!(body, "    consent: {}", variable)?;
+            variables.push(format!("{}: OwnerConsent", variable));
         }
-        println!("    scoringChainId: {}", scoring_chain_id.to_value());
-        println!("  )");
+        writeln!(
+            body,
+            "    scoringChainIds: {}",
+            scoring_chain_ids.to_value()
+        )?;
+        writeln!(body, "  )")?;
     }
 
+    if variables.is_empty() {
+        println!("mutation {{");
+    } else {
+        println!("mutation ({}) {{", variables.join(", "));
+    }
+    print!("{}", body);
     println!("}}");
 
     Ok(())
 }
+
+fn generate_campaign_mutation(
+    blob_map_path: &PathBuf,
+    campaign: &str,
+    all: bool,
+    name: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let puzzles_info = get_puzzles(all, name);
+
+    // Load blob mapping
+    let blob_map: HashMap<String, DataBlobHash> = {
+        if blob_map_path.exists() {
+            let blob_map_content = fs::read_to_string(blob_map_path)?;
+            serde_json::from_str(&blob_map_content)?
+        } else {
+            panic!(
+                "Error: Blob map file not found: {}",
+                blob_map_path.display()
+            );
+        }
+    };
+
+    // Group puzzles into stages by the tens digit of their number, e.g. `2x_` puzzles
+    // form one stage.
+    let mut stages = BTreeMap::<u32, Vec<DataBlobHash>>::new();
+    for (name, _) in puzzles_info {
+        let Some(puzzle_id) = blob_map.get(name) else {
+            eprintln!("Warning: Puzzle ID for name {} is missing, skipping", name);
+            continue;
+        };
+        let number = name
+            .get(..2)
+            .and_then(|prefix| prefix.parse::<u32>().ok())
+            .ok_or_else(|| format!("Puzzle name {name} should start with a number"))?;
+        stages.entry(number / 10).or_default().push(*puzzle_id);
+    }
+
+    // Every puzzle of a stage requires all the puzzles of the previous stage.
+    let mut puzzles = Vec::new();
+    let mut prerequisites = Vec::new();
+    for puzzle_ids in stages.into_values() {
+        for puzzle_id in &puzzle_ids {
+            puzzles.push(CampaignPuzzle {
+                puzzle_id: *puzzle_id,
+                prerequisites: prerequisites.clone(),
+            });
+        }
+        prerequisites = puzzle_ids;
+    }
+    validate_campaign(&puzzles)?;
+
+    println!("mutation {{");
+    println!("  createCampaign(");
+    println!("    name: {}", campaign.to_value());
+    println!("    puzzles: {}", puzzles.to_value());
+    println!("  )");
+    println!("}}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::CryptoHash;
+
+    use super::*;
+
+    #[test]
+    fn test_boat_template() {
+        let (puzzle, solution) = create_boat_puzzle_and_solution();
+        let template = PuzzleTemplate {
+            puzzle,
+            max_offset_x: 2,
+            max_offset_y: 2,
+            allow_mirroring: true,
+        };
+        template.validate().unwrap();
+        let template_id = DataBlobHash(CryptoHash::test_hash("boat"));
+        for index in 0..8 {
+            let owner = AccountOwner::from(CryptoHash::test_hash(format!("owner{index}")));
+            let parameters = template.parameters(template_id, &owner);
+            let puzzle = template.instance(template_id, &owner);
+            puzzle.validate().unwrap();
+            let board = parameters.board(&solution, template.puzzle.size);
+            assert_eq!(puzzle.check_solution(&board), Ok(1));
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::linera_base_types::DataBlobHash;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A puzzle of a campaign, with the puzzles to solve before it counts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "CampaignPuzzleInput")]
+pub struct CampaignPuzzle {
+    /// The ID of the puzzle.
+    pub puzzle_id: DataBlobHash,
+    /// The puzzles of the same campaign that must be solved first.
+    pub prerequisites: Vec<DataBlobHash>,
+}
+
+/// The reason why a campaign is invalid.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InvalidCampaign {
+    /// The campaign has no puzzles.
+    #[error("The campaign has no puzzles")]
+    Empty,
+    /// A puzzle appears twice in the campaign.
+    #[error("Puzzle #{index} appears twice in the campaign")]
+    DuplicatePuzzle {
+        /// The index of the second occurrence.
+        index: usize,
+    },
+    /// A prerequisite does not appear before the puzzle in the campaign.
+    #[error("Puzzle #{index} has a prerequisite that does not come before it")]
+    UnorderedPrerequisite {
+        /// The index of the puzzle.
+        index: usize,
+    },
+}
+
+/// Checks that a campaign is not empty, that its puzzles are distinct, and that the
+/// prerequisites of each puzzle come before it. This guarantees that every puzzle can be
+/// unlocked.
+pub fn validate_campaign(puzzles: &[CampaignPuzzle]) -> Result<(), InvalidCampaign> {
+    if puzzles.is_empty() {
+        return Err(InvalidCampaign::Empty);
+    }
+    let mut previous_puzzles = BTreeSet::new();
+    for (index, puzzle) in puzzles.iter().enumerate() {
+        if !puzzle
+            .prerequisites
+            .iter()
+            .all(|prerequisite| previous_puzzles.contains(prerequisite))
+        {
+            return Err(InvalidCampaign::UnorderedPrerequisite { index });
+        }
+        if !previous_puzzles.insert(puzzle.puzzle_id) {
+            return Err(InvalidCampaign::DuplicatePuzzle { index });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::CryptoHash;
+
+    use super::*;
+
+    fn puzzle(name: &str, prerequisites: &[&str]) -> CampaignPuzzle {
+        CampaignPuzzle {
+            puzzle_id: DataBlobHash(CryptoHash::test_hash(name)),
+            prerequisites: prerequisites
+                .iter()
+                .map(|name| DataBlobHash(CryptoHash::test_hash(*name)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate_campaign() {
+        assert_eq!(validate_campaign(&[]), Err(InvalidCampaign::Empty));
+        assert_eq!(
+            validate_campaign(&[
+                puzzle("block", &[]),
+                puzzle("beehive", &[]),
+                puzzle("glider", &["block", "beehive"]),
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            validate_campaign(&[puzzle("block", &["glider"]), puzzle("glider", &[])]),
+            Err(InvalidCampaign::UnorderedPrerequisite { index: 0 })
+        );
+        assert_eq!(
+            validate_campaign(&[puzzle("block", &["block"])]),
+            Err(InvalidCampaign::UnorderedPrerequisite { index: 0 })
+        );
+        assert_eq!(
+            validate_campaign(&[puzzle("block", &[]), puzzle("block", &[])]),
+            Err(InvalidCampaign::DuplicatePuzzle { index: 1 })
+        );
+    }
+}
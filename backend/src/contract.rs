@@ -3,17 +3,43 @@
 
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
+mod legacy;
+mod migration;
 mod state;
 
 use async_graphql::ComplexObject;
-use gol_challenge::{game::Puzzle, GolChallengeAbi, Operation};
+use gol_challenge::{
+    achievements::{DifficultyCounts, SolverFacts},
+    board_hash,
+    campaign::validate_campaign,
+    day_index,
+    duel::{decide_duel, DuelId, DuelOutcome},
+    game::{Board, Difficulty, PlayerColor, Position},
+    immigration::{GameId, ImmigrationGame},
+    points::LeaderboardKey,
+    quality::{QualityKey, QualityMetric, QualityMetrics},
+    solution_commitment,
+    teams::{TeamKey, TeamPolicy},
+    templates::PuzzleBlob,
+    votes::PuzzleVote,
+    GolChallengeAbi, GolEvent, Operation, OwnerConsent, SolutionCriterion, SubmissionConsent,
+    PUZZLES_STREAM_NAME, SOLUTIONS_STREAM_NAME, SOLUTION_COMMIT_TTL_MICROS,
+};
 use linera_sdk::{
-    linera_base_types::{AccountOwner, DataBlobHash, Timestamp, WithContractAbi},
+    linera_base_types::{
+        Account, AccountOwner, Amount, ChainId, CryptoHash, DataBlobHash, StreamName, Timestamp,
+        WithContractAbi,
+    },
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 use serde::{Deserialize, Serialize};
-use state::{GolChallengeState, Solution};
+use state::{
+    Bounty, Campaign, CreditedSolution, Duel, DuelRecord, DuelSolution, DuelStatus,
+    GolChallengeState, ImmigrationMatch, Leaderboard, OwnerScore, QualityBoard, RegisteredPuzzle,
+    RewardPayout, ScoringOutcome, ScoringStatus, Season, Solution, SolutionCommit, SolutionRecord,
+    SolveEvent, Team, VerifiedPuzzle, SCHEMA_VERSION,
+};
 
 pub struct GolChallengeContract {
     state: GolChallengeState,
@@ -27,23 +53,153 @@ impl WithContractAbi for GolChallengeContract {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct Message {
-    /// The ID of the puzzle that was solved.
-    pub puzzle_id: DataBlobHash,
-    /// The timestamp of the solution.
-    pub timestamp: Timestamp,
-    /// The user credited for the solution.
-    pub owner: AccountOwner,
+pub enum Message {
+    /// A solution reported by a user chain to a scoring chain. The board is included so
+    /// that the scoring chain may verify the solution again.
+    SolutionReport {
+        /// The ID of the puzzle that was solved.
+        puzzle_id: DataBlobHash,
+        /// The board of the solution.
+        board: Board,
+        /// The timestamp of the solution.
+        timestamp: Timestamp,
+        /// The user credited for the solution.
+        owner: AccountOwner,
+    },
+    /// The commitment to a solution, sent by a user chain to a scoring chain before
+    /// revealing the solution. Authenticated by the owner credited for the solution.
+    SolutionCommit {
+        /// The ID of the puzzle that was solved.
+        puzzle_id: DataBlobHash,
+        /// The commitment to the board, the owner and a secret salt.
+        commitment: String,
+    },
+    /// A solution revealed by a user chain to a scoring chain that previously received
+    /// its commitment.
+    SolutionReveal {
+        /// The ID of the puzzle that was solved.
+        puzzle_id: DataBlobHash,
+        /// The board of the solution.
+        board: Board,
+        /// The salt used in the commitment.
+        salt: String,
+        /// The timestamp of the solution on the user chain.
+        timestamp: Timestamp,
+        /// The user credited for the solution.
+        owner: AccountOwner,
+    },
+    /// A bounty posted by a user chain. The amount was transferred to the application on
+    /// the scoring chain.
+    BountyPosted {
+        /// The ID of the puzzle of the bounty.
+        puzzle_id: DataBlobHash,
+        /// The amount of the bounty.
+        amount: Amount,
+        /// The time after which the bounty may be reclaimed instead.
+        expiry: Timestamp,
+        /// The owner that posted the bounty.
+        poster: AccountOwner,
+    },
+    /// A request to reclaim an expired bounty, authenticated by the poster.
+    BountyReclaim {
+        /// The ID of the puzzle of the bounty.
+        puzzle_id: DataBlobHash,
+    },
+    /// A duel sent by the chain of the challenger to the chain of the opponent.
+    DuelChallenge {
+        /// The new duel.
+        duel: Duel,
+    },
+    /// The acceptance of a duel, sent by the chain of the opponent.
+    DuelAccepted {
+        /// The ID of the duel.
+        duel_id: DuelId,
+    },
+    /// A solution of a player, sent to the chain of the other player to be verified and
+    /// timed.
+    DuelSolution {
+        /// The ID of the duel.
+        duel_id: DuelId,
+        /// The board of the solution.
+        board: Board,
+    },
+    /// A new Immigration game, sent by the chain of the first player.
+    ImmigrationGameCreated {
+        /// The new game.
+        game: ImmigrationMatch,
+    },
+    /// A turn played in an Immigration game, sent to the chain of the other player.
+    ImmigrationTurnPlayed {
+        /// The ID of the game.
+        game_id: GameId,
+        /// The cells placed by the player.
+        cells: Vec<Position>,
+    },
+    /// A vote on a puzzle, authenticated by the voter.
+    PuzzleVote {
+        /// The ID of the puzzle.
+        puzzle_id: DataBlobHash,
+        /// The rating and the perceived difficulty.
+        vote: PuzzleVote,
+    },
+    /// A request of the authenticated owner about a team of the scoring chain.
+    TeamRequest {
+        /// The name of the team.
+        name: String,
+        /// The request.
+        request: TeamRequest,
+    },
+    /// A puzzle published with a reference solution, authenticated by its creator.
+    PuzzlePublished {
+        /// The ID of the new puzzle blob.
+        puzzle_id: DataBlobHash,
+        /// The hash of the reference solution.
+        solution_hash: CryptoHash,
+        /// When the puzzle was published.
+        timestamp: Timestamp,
+    },
+    /// The outcome of a reported solution, sent back by the scoring chain.
+    SolutionOutcome {
+        /// The ID of the puzzle that was solved.
+        puzzle_id: DataBlobHash,
+        /// The timestamp of the reported solution.
+        timestamp: Timestamp,
+        /// How the solution was scored.
+        outcome: ScoringOutcome,
+    },
+}
+
+/// A request about a team, sent by a user chain to a scoring chain.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum TeamRequest {
+    /// Create the team.
+    Create {
+        /// How the score of the team is computed.
+        policy: TeamPolicy,
+    },
+    /// Invite an owner to the team.
+    Invite {
+        /// The owner to invite.
+        member: AccountOwner,
+    },
+    /// Join the team.
+    Join,
+    /// Change how the score of the team is computed.
+    SetPolicy {
+        /// The new policy.
+        policy: TeamPolicy,
+    },
 }
 
 impl Contract for GolChallengeContract {
     type Message = Message;
     type InstantiationArgument = ();
     type Parameters = ();
-    type EventValue = ();
+    type EventValue = GolEvent;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
-        let state = GolChallengeState::load(runtime.root_view_storage_context())
+        let context = runtime.root_view_storage_context();
+        let state = migration::load_and_migrate(context)
             .await
             .expect("Failed to load state");
         GolChallengeContract { state, runtime }
@@ -62,76 +218,3997 @@ impl Contract for GolChallengeContract {
                 puzzle_id,
                 board,
                 owner,
-                scoring_chain_id,
+                consent,
+                scoring_chain_ids,
+            } => {
+                self.submit_solution(puzzle_id, board, owner, consent, scoring_chain_ids, None)
+                    .await;
+            }
+            Operation::CommitSolution {
+                puzzle_id,
+                commitment,
+                owner,
+                scoring_chain_ids,
             } => {
-                let owner = owner.unwrap_or_else(|| {
+                // Scoring chains rate-limit commitments by owner, so the owner must sign.
+                let signer = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Committing to a solution requires an authenticated owner");
+                assert!(
+                    owner.is_none_or(|owner| owner == signer),
+                    "Only the credited owner may commit to a solution"
+                );
+                for chain_id in scoring_chain_ids {
+                    let message = Message::SolutionCommit {
+                        puzzle_id,
+                        commitment: commitment.clone(),
+                    };
                     self.runtime
-                        .authenticated_signer()
-                        .expect("Operation must have an owner or be authenticated.")
-                });
-                let puzzle_bytes = self.runtime.read_data_blob(puzzle_id);
-                let puzzle = bcs::from_bytes::<Puzzle>(&puzzle_bytes).expect("Deserialize puzzle");
-                puzzle.check_solution(&board).expect("Invalid solution");
-                let timestamp = self.runtime.system_time();
-                let solution = Solution {
+                        .prepare_message(message)
+                        .with_authentication()
+                        .send_to(chain_id);
+                }
+            }
+            Operation::RevealSolution {
+                puzzle_id,
+                board,
+                salt,
+                owner,
+                consent,
+                scoring_chain_ids,
+            } => {
+                self.submit_solution(
+                    puzzle_id,
                     board,
-                    timestamp,
                     owner,
-                };
+                    consent,
+                    scoring_chain_ids,
+                    Some(salt),
+                )
+                .await;
+            }
+            Operation::ReportSolution {
+                puzzle_id,
+                scoring_chain_ids,
+                consent,
+            } => {
+                let mut solution = self
+                    .state
+                    .solutions
+                    .get(&puzzle_id)
+                    .await
+                    .unwrap()
+                    .expect("No solution was submitted for this puzzle");
+                self.check_consent(
+                    solution.owner,
+                    consent,
+                    puzzle_id,
+                    &solution.board,
+                    &scoring_chain_ids,
+                )
+                .await;
+                self.report_solution(puzzle_id, &mut solution, scoring_chain_ids, None);
                 self.state
                     .solutions
                     .insert(&puzzle_id, solution)
                     .expect("Store solution");
-
-                if let Some(scoring_chain_id) = scoring_chain_id {
-                    let message = Message {
+            }
+            Operation::PostBounty {
+                puzzle_id,
+                amount,
+                expiry,
+                scoring_chain_id,
+            } => {
+                let poster = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Posting a bounty requires an authenticated owner");
+                self.read_puzzle(puzzle_id)
+                    .validate()
+                    .expect("Invalid puzzle");
+                assert!(
+                    expiry > self.runtime.system_time(),
+                    "Bounty expiry is in the past"
+                );
+                let escrow = Account {
+                    chain_id: scoring_chain_id,
+                    owner: self.application_owner(),
+                };
+                self.runtime.transfer(poster, escrow, amount);
+                let message = Message::BountyPosted {
+                    puzzle_id,
+                    amount,
+                    expiry,
+                    poster,
+                };
+                self.runtime
+                    .prepare_message(message)
+                    .send_to(scoring_chain_id);
+            }
+            Operation::ReclaimBounty {
+                puzzle_id,
+                scoring_chain_id,
+            } => {
+                assert!(
+                    self.runtime.authenticated_signer().is_some(),
+                    "Reclaiming a bounty requires an authenticated owner"
+                );
+                self.runtime
+                    .prepare_message(Message::BountyReclaim { puzzle_id })
+                    .with_authentication()
+                    .send_to(scoring_chain_id);
+            }
+            Operation::CreateDuel {
+                puzzle_id,
+                opponent_chain_id,
+                opponent,
+                deadline,
+                metric,
+            } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Creating a duel requires an authenticated owner");
+                let chain_id = self.runtime.chain_id();
+                assert_ne!(
+                    opponent_chain_id, chain_id,
+                    "Duels are between two user chains"
+                );
+                self.read_puzzle(puzzle_id)
+                    .validate()
+                    .expect("Invalid puzzle");
+                assert!(
+                    deadline > self.runtime.system_time(),
+                    "Duel deadline is in the past"
+                );
+                let index = *self.state.duel_count.get();
+                self.state.duel_count.set(index + 1);
+                let duel = Duel {
+                    id: DuelId { chain_id, index },
+                    puzzle_id,
+                    challenger: Account { chain_id, owner },
+                    opponent: Account {
+                        chain_id: opponent_chain_id,
+                        owner: opponent,
+                    },
+                    deadline,
+                    metric,
+                    status: DuelStatus::Pending,
+                    challenger_solution: None,
+                    opponent_solution: None,
+                    outcome: None,
+                };
+                self.runtime
+                    .prepare_message(Message::DuelChallenge { duel: duel.clone() })
+                    .send_to(opponent_chain_id);
+                self.state.duels.insert(&duel.id, duel).unwrap();
+            }
+            Operation::AcceptDuel { duel_id } => {
+                let mut duel = self.duel(duel_id).await;
+                assert_eq!(
+                    self.runtime.authenticated_signer(),
+                    Some(duel.opponent.owner),
+                    "Only the opponent may accept a duel"
+                );
+                assert_eq!(duel.status, DuelStatus::Pending, "Duel is not pending");
+                assert!(
+                    self.runtime.system_time() <= duel.deadline,
+                    "Duel has expired"
+                );
+                duel.status = DuelStatus::Accepted;
+                self.runtime
+                    .prepare_message(Message::DuelAccepted { duel_id })
+                    .send_to(duel.challenger.chain_id);
+                self.state.duels.insert(&duel_id, duel).unwrap();
+            }
+            Operation::SubmitDuelSolution { duel_id, board } => {
+                let mut duel = self.duel(duel_id).await;
+                let chain_id = self.runtime.chain_id();
+                let (player, other_chain_id) = if chain_id == duel.challenger.chain_id {
+                    (duel.challenger, duel.opponent.chain_id)
+                } else {
+                    (duel.opponent, duel.challenger.chain_id)
+                };
+                assert_eq!(
+                    self.runtime.authenticated_signer(),
+                    Some(player.owner),
+                    "Only a player of the duel may submit a solution"
+                );
+                assert_eq!(duel.status, DuelStatus::Accepted, "Duel is not accepted");
+                let timestamp = self.runtime.system_time();
+                assert!(timestamp <= duel.deadline, "Duel has expired");
+                let metrics = self
+                    .duel_solution_metrics(duel.puzzle_id, &board, player.owner)
+                    .expect("Invalid solution");
+                let solution = duel.solution_mut(chain_id);
+                assert!(solution.is_none(), "A solution was already submitted");
+                *solution = Some(DuelSolution {
+                    board: board.clone(),
+                    timestamp,
+                    metrics,
+                });
+                self.runtime
+                    .prepare_message(Message::DuelSolution { duel_id, board })
+                    .send_to(other_chain_id);
+                self.store_duel(duel).await;
+            }
+            Operation::ResolveDuel { duel_id } => {
+                let mut duel = self.duel(duel_id).await;
+                assert!(
+                    self.runtime.system_time() > duel.deadline,
+                    "Duel deadline has not passed"
+                );
+                match duel.status {
+                    DuelStatus::Pending => {
+                        duel.status = DuelStatus::Expired;
+                        self.state.duels.insert(&duel_id, duel).unwrap();
+                    }
+                    DuelStatus::Accepted => self.resolve_duel(duel).await,
+                    DuelStatus::Resolved | DuelStatus::Expired => {
+                        panic!("Duel is already closed")
+                    }
+                }
+            }
+            Operation::CreateImmigrationGame {
+                opponent_chain_id,
+                opponent,
+                rules,
+            } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Creating a game requires an authenticated owner");
+                let chain_id = self.runtime.chain_id();
+                assert_ne!(
+                    opponent_chain_id, chain_id,
+                    "Games are between two user chains"
+                );
+                let game = ImmigrationGame::new(rules).expect("Invalid rules");
+                let index = *self.state.immigration_game_count.get();
+                self.state.immigration_game_count.set(index + 1);
+                let game = ImmigrationMatch {
+                    id: GameId { chain_id, index },
+                    first_player: Account { chain_id, owner },
+                    second_player: Account {
+                        chain_id: opponent_chain_id,
+                        owner: opponent,
+                    },
+                    game,
+                };
+                self.runtime
+                    .prepare_message(Message::ImmigrationGameCreated { game: game.clone() })
+                    .send_to(opponent_chain_id);
+                self.state.immigration_games.insert(&game.id, game).unwrap();
+            }
+            Operation::PlayImmigrationTurn { game_id, cells } => {
+                let mut game = self
+                    .state
+                    .immigration_games
+                    .get(&game_id)
+                    .await
+                    .unwrap()
+                    .expect("Unknown game");
+                let chain_id = self.runtime.chain_id();
+                let (color, player, other_chain_id) = if chain_id == game.first_player.chain_id {
+                    (
+                        PlayerColor::First,
+                        game.first_player,
+                        game.second_player.chain_id,
+                    )
+                } else {
+                    (
+                        PlayerColor::Second,
+                        game.second_player,
+                        game.first_player.chain_id,
+                    )
+                };
+                assert_eq!(
+                    self.runtime.authenticated_signer(),
+                    Some(player.owner),
+                    "Only a player of the game may play"
+                );
+                game.game.play(color, &cells).expect("Invalid move");
+                self.runtime
+                    .prepare_message(Message::ImmigrationTurnPlayed { game_id, cells })
+                    .send_to(other_chain_id);
+                self.state.immigration_games.insert(&game_id, game).unwrap();
+            }
+            Operation::SetBestSolutionCriterion { criterion } => {
+                self.state.best_solution_criterion.set(criterion);
+            }
+            Operation::RatePuzzle {
+                puzzle_id,
+                vote,
+                scoring_chain_ids,
+            } => {
+                assert!(
+                    self.runtime.authenticated_signer().is_some(),
+                    "Voting requires an authenticated owner"
+                );
+                assert!(vote.is_valid(), "Invalid rating");
+                for chain_id in scoring_chain_ids {
+                    let message = Message::PuzzleVote {
+                        puzzle_id,
+                        vote: vote.clone(),
+                    };
+                    self.runtime
+                        .prepare_message(message)
+                        .with_authentication()
+                        .send_to(chain_id);
+                }
+            }
+            Operation::PublishPuzzle {
+                puzzle,
+                reference_solution,
+                scoring_chain_ids,
+            } => {
+                let creator = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Publishing a puzzle requires an authenticated owner");
+                puzzle.validate().expect("Invalid puzzle");
+                let mut checked_puzzle = puzzle.clone();
+                checked_puzzle.enforce_initial_conditions = true;
+                checked_puzzle
+                    .check_solution(&reference_solution)
+                    .expect("The reference solution does not solve the puzzle");
+                let puzzle_bytes = bcs::to_bytes(&puzzle).expect("Serialize puzzle");
+                let puzzle_id = self.runtime.create_data_blob(puzzle_bytes);
+                assert!(
+                    !self
+                        .state
+                        .verified_puzzles
+                        .contains_key(&puzzle_id)
+                        .await
+                        .unwrap(),
+                    "Puzzle was already published"
+                );
+                let solution_hash = board_hash(&reference_solution);
+                let timestamp = self.runtime.system_time();
+                let verified_puzzle = VerifiedPuzzle {
+                    creator,
+                    solution_hash,
+                    published_at: timestamp,
+                };
+                self.state
+                    .verified_puzzles
+                    .insert(&puzzle_id, verified_puzzle)
+                    .unwrap();
+                for chain_id in scoring_chain_ids {
+                    let message = Message::PuzzlePublished {
                         puzzle_id,
+                        solution_hash,
                         timestamp,
-                        owner,
                     };
                     self.runtime
                         .prepare_message(message)
-                        .send_to(scoring_chain_id);
+                        .with_authentication()
+                        .send_to(chain_id);
                 }
             }
+            Operation::CreateTeam {
+                name,
+                policy,
+                scoring_chain_id,
+            } => {
+                self.send_team_request(name, TeamRequest::Create { policy }, scoring_chain_id);
+            }
+            Operation::InviteTeamMember {
+                name,
+                member,
+                scoring_chain_id,
+            } => {
+                self.send_team_request(name, TeamRequest::Invite { member }, scoring_chain_id);
+            }
+            Operation::JoinTeam {
+                name,
+                scoring_chain_id,
+            } => {
+                self.send_team_request(name, TeamRequest::Join, scoring_chain_id);
+            }
+            Operation::SetTeamPolicy {
+                name,
+                policy,
+                scoring_chain_id,
+            } => {
+                self.send_team_request(name, TeamRequest::SetPolicy { policy }, scoring_chain_id);
+            }
             Operation::RegisterPuzzle { puzzle_id } => {
                 // Puzzles are only registered on a scoring chain.
+                let puzzle_blob = self.read_puzzle(puzzle_id);
+                puzzle_blob.validate().expect("Invalid puzzle");
+                if let Some(ranking) = puzzle_blob.ranking() {
+                    self.state
+                        .puzzle_rankings
+                        .insert(&puzzle_id, ranking.clone())
+                        .unwrap();
+                }
+                let puzzle = puzzle_blob.into_base_puzzle();
+                let registered_at = match self.state.puzzle_catalog.get(&puzzle_id).await.unwrap() {
+                    Some(registered_puzzle) => registered_puzzle.registered_at,
+                    None => self.runtime.system_time(),
+                };
+                if !self
+                    .state
+                    .registered_puzzles
+                    .contains(&puzzle_id)
+                    .await
+                    .unwrap()
+                {
+                    *self
+                        .state
+                        .registered_counts
+                        .get_mut_or_default(&puzzle.difficulty)
+                        .await
+                        .unwrap() += 1;
+                    if let Some(verified_puzzle) =
+                        self.state.verified_puzzles.get(&puzzle_id).await.unwrap()
+                    {
+                        self.credit_creator(verified_puzzle.creator).await;
+                    }
+                }
+                let registered_puzzle = RegisteredPuzzle {
+                    title: puzzle.title,
+                    difficulty: puzzle.difficulty,
+                    size: puzzle.size,
+                    registered_at,
+                };
+                if self
+                    .state
+                    .legacy_puzzles
+                    .contains(&puzzle_id)
+                    .await
+                    .unwrap()
+                {
+                    self.credit_legacy_solvers(puzzle_id, registered_puzzle.difficulty)
+                        .await;
+                    self.state.legacy_puzzles.remove(&puzzle_id).unwrap();
+                }
                 self.state.registered_puzzles.insert(&puzzle_id).unwrap();
+                self.state
+                    .puzzle_catalog
+                    .insert(&puzzle_id, registered_puzzle)
+                    .unwrap();
+                self.runtime.emit(
+                    StreamName(PUZZLES_STREAM_NAME.to_vec()),
+                    &GolEvent::PuzzleRegistered { puzzle_id },
+                );
+            }
+            Operation::SetSolutionVerification { enabled } => {
+                self.state.verify_reported_solutions.set(enabled);
+            }
+            Operation::SetPointsConfig { config } => {
+                self.state.points_config.set(config);
+            }
+            Operation::SetRateLimits { limits } => {
+                self.state.rate_limits.set(limits);
+            }
+            Operation::SetPublicationPoints { points } => {
+                self.state.publication_points.set(points);
+            }
+            Operation::CreateAchievement { achievement } => {
+                assert!(
+                    !self
+                        .state
+                        .achievements
+                        .contains_key(&achievement.name)
+                        .await
+                        .unwrap(),
+                    "Achievement already exists"
+                );
+                self.state
+                    .achievements
+                    .insert(&achievement.name.clone(), achievement)
+                    .unwrap();
+            }
+            Operation::CreateCampaign { name, puzzles } => {
+                validate_campaign(&puzzles).expect("Invalid campaign");
+                assert!(
+                    !self.state.campaigns.contains_key(&name).await.unwrap(),
+                    "Campaign already exists"
+                );
+                for puzzle in &puzzles {
+                    assert!(
+                        self.state
+                            .registered_puzzles
+                            .contains(&puzzle.puzzle_id)
+                            .await
+                            .unwrap(),
+                        "Puzzle is not registered"
+                    );
+                    assert!(
+                        !self
+                            .state
+                            .puzzle_campaigns
+                            .contains_key(&puzzle.puzzle_id)
+                            .await
+                            .unwrap(),
+                        "Puzzle already belongs to a campaign"
+                    );
+                    self.state
+                        .puzzle_campaigns
+                        .insert(&puzzle.puzzle_id, name.clone())
+                        .unwrap();
+                }
+                let campaign = Campaign {
+                    name: name.clone(),
+                    puzzles,
+                };
+                self.state.campaigns.insert(&name, campaign).unwrap();
+            }
+            Operation::ScheduleDailyPuzzles { puzzle_ids } => {
+                let today = day_index(self.runtime.system_time());
+                let mut day = (*self.state.daily_schedule_end.get()).max(today);
+                for puzzle_id in puzzle_ids {
+                    assert!(
+                        self.state
+                            .registered_puzzles
+                            .contains(&puzzle_id)
+                            .await
+                            .unwrap(),
+                        "Puzzle is not registered"
+                    );
+                    assert!(
+                        !self
+                            .state
+                            .scheduled_puzzles
+                            .contains(&puzzle_id)
+                            .await
+                            .unwrap(),
+                        "Puzzle is already scheduled"
+                    );
+                    self.state.scheduled_puzzles.insert(&puzzle_id).unwrap();
+                    self.state.daily_puzzles.insert(&day, puzzle_id).unwrap();
+                    day += 1;
+                }
+                self.state.daily_schedule_end.set(day);
+            }
+            Operation::SetDailyConfig { config } => {
+                self.state.daily_config.set(config);
+            }
+            Operation::FundRewardPool { amount } => {
+                // The pool is funded from the balance of the scoring chain, which requires
+                // an owner of the chain.
+                assert!(
+                    self.runtime.authenticated_signer().is_some(),
+                    "Funding the reward pool requires an authenticated owner"
+                );
+                let destination = Account {
+                    chain_id: self.runtime.chain_id(),
+                    owner: self.application_owner(),
+                };
+                self.runtime
+                    .transfer(AccountOwner::CHAIN, destination, amount);
+                let balance = self.state.reward_pool_balance.get().saturating_add(amount);
+                self.state.reward_pool_balance.set(balance);
+            }
+            Operation::SetPuzzleReward { puzzle_id, reward } => match reward {
+                Some(reward) => {
+                    assert!(
+                        self.state
+                            .registered_puzzles
+                            .contains(&puzzle_id)
+                            .await
+                            .unwrap(),
+                        "Puzzle is not registered"
+                    );
+                    self.state
+                        .puzzle_rewards
+                        .insert(&puzzle_id, reward)
+                        .unwrap();
+                }
+                None => self.state.puzzle_rewards.remove(&puzzle_id).unwrap(),
+            },
+            Operation::CreateSeason {
+                name,
+                start,
+                end,
+                puzzle_ids,
+            } => {
+                assert!(start < end, "Invalid season window");
+                assert!(
+                    !self.state.seasons.contains_key(&name).await.unwrap(),
+                    "Season already exists"
+                );
+                let season = Season {
+                    name: name.clone(),
+                    start,
+                    end,
+                    puzzle_ids,
+                };
+                self.state.seasons.insert(&name, season).unwrap();
+            }
+            Operation::MigrateState => {
+                // The state was migrated when it was loaded for this block.
+                assert_eq!(*self.state.schema_version.get(), SCHEMA_VERSION);
             }
         }
     }
 
     async fn execute_message(&mut self, message: Message) {
         log::trace!("Handling message {:?}", message);
-        let Message {
+        match message {
+            Message::SolutionReport {
+                puzzle_id,
+                board,
+                timestamp,
+                owner,
+            } => {
+                if self.is_rate_limited(owner).await {
+                    return;
+                }
+                let outcome = self
+                    .score_solution(puzzle_id, board, timestamp, owner, None)
+                    .await;
+                self.send_outcome(puzzle_id, timestamp, outcome);
+            }
+            Message::SolutionCommit {
+                puzzle_id,
+                commitment,
+            } => {
+                let Some(owner) = self.runtime.authenticated_signer() else {
+                    log::trace!("Ignoring unauthenticated commitment");
+                    return;
+                };
+                if self.is_rate_limited(owner).await {
+                    return;
+                }
+                let now = self.runtime.system_time();
+                let key = (owner, commitment);
+                if let Some(commit) = self.state.solution_commits.get(&key).await.unwrap() {
+                    if now <= commit.deadline() {
+                        log::trace!("Ignoring duplicate commitment");
+                        return;
+                    }
+                }
+                // No rank is reserved: the revealed solution is ranked after the owners
+                // credited before now, whatever happens in between.
+                let credited_before = self
+                    .state
+                    .solver_counts
+                    .get(&puzzle_id)
+                    .await
+                    .unwrap()
+                    .unwrap_or(0);
+                let commit = SolutionCommit {
+                    puzzle_id,
+                    timestamp: now,
+                    credited_before,
+                };
+                self.state
+                    .commit_deadlines
+                    .insert(&puzzle_id, commit.deadline())
+                    .unwrap();
+                self.state.solution_commits.insert(&key, commit).unwrap();
+            }
+            Message::SolutionReveal {
+                puzzle_id,
+                board,
+                salt,
+                timestamp,
+                owner,
+            } => {
+                if self.is_rate_limited(owner).await {
+                    return;
+                }
+                // Only a commitment authenticated by the credited owner matches.
+                let key = (owner, solution_commitment(&board, &owner, &salt));
+                let commit = self.state.solution_commits.get(&key).await.unwrap();
+                let outcome = match commit {
+                    Some(commit) if commit.puzzle_id == puzzle_id => {
+                        self.state.solution_commits.remove(&key).unwrap();
+                        if self.runtime.system_time() > commit.deadline() {
+                            log::trace!("Scoring a solution revealed after the commitment expired");
+                            self.score_solution(puzzle_id, board, timestamp, owner, None)
+                                .await
+                        } else {
+                            self.score_solution(
+                                puzzle_id,
+                                board,
+                                commit.timestamp,
+                                owner,
+                                Some(commit.credited_before),
+                            )
+                            .await
+                        }
+                    }
+                    _ => {
+                        log::trace!("Rejecting solution without a matching commitment");
+                        ScoringOutcome::Rejected
+                    }
+                };
+                self.send_outcome(puzzle_id, timestamp, outcome);
+            }
+            Message::BountyPosted {
+                puzzle_id,
+                amount,
+                expiry,
+                poster,
+            } => {
+                let poster = Account {
+                    chain_id: self
+                        .runtime
+                        .message_origin_chain_id()
+                        .expect("Incoming message must have an origin"),
+                    owner: poster,
+                };
+                let is_duplicate = self.state.bounties.contains_key(&puzzle_id).await.unwrap();
+                if is_duplicate || expiry <= self.runtime.system_time() {
+                    log::trace!("Refunding a duplicate or expired bounty");
+                    let application_owner = self.application_owner();
+                    self.runtime.transfer(application_owner, poster, amount);
+                    return;
+                }
+                let bounty = Bounty {
+                    puzzle_id,
+                    poster,
+                    amount,
+                    expiry,
+                };
+                self.state.bounties.insert(&puzzle_id, bounty).unwrap();
+            }
+            Message::BountyReclaim { puzzle_id } => {
+                let Some(bounty) = self.state.bounties.get(&puzzle_id).await.unwrap() else {
+                    log::trace!("Ignoring reclaim of a missing bounty");
+                    return;
+                };
+                let sender = Account {
+                    chain_id: self
+                        .runtime
+                        .message_origin_chain_id()
+                        .expect("Incoming message must have an origin"),
+                    owner: self
+                        .runtime
+                        .authenticated_signer()
+                        .expect("Reclaims must be authenticated"),
+                };
+                if sender != bounty.poster || self.runtime.system_time() < bounty.expiry {
+                    log::trace!("Ignoring unauthorized or early reclaim of a bounty");
+                    return;
+                }
+                let application_owner = self.application_owner();
+                self.runtime
+                    .transfer(application_owner, bounty.poster, bounty.amount);
+                self.state.bounties.remove(&puzzle_id).unwrap();
+            }
+            Message::DuelChallenge { duel } => {
+                let origin_chain_id = self
+                    .runtime
+                    .message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                if duel.id.chain_id != origin_chain_id
+                    || duel.challenger.chain_id != origin_chain_id
+                    || self.state.duels.contains_key(&duel.id).await.unwrap()
+                {
+                    log::trace!("Ignoring invalid or duplicate duel");
+                    return;
+                }
+                self.state.duels.insert(&duel.id, duel).unwrap();
+            }
+            Message::DuelAccepted { duel_id } => {
+                let origin_chain_id = self
+                    .runtime
+                    .message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                let Some(duel) = self.state.duels.get_mut(&duel_id).await.unwrap() else {
+                    return;
+                };
+                // The acceptance may be received after this chain expired the duel.
+                let is_open = matches!(duel.status, DuelStatus::Pending | DuelStatus::Expired);
+                if is_open && duel.opponent.chain_id == origin_chain_id {
+                    duel.status = DuelStatus::Accepted;
+                }
+            }
+            Message::DuelSolution { duel_id, board } => {
+                let Some(mut duel) = self.state.duels.get(&duel_id).await.unwrap() else {
+                    return;
+                };
+                // The sender cannot be trusted with the time of its solution, so solutions
+                // are timed when they are received.
+                let timestamp = self.runtime.system_time();
+                if timestamp > duel.deadline {
+                    log::trace!("Ignoring late duel solution");
+                    return;
+                }
+                let origin_chain_id = self
+                    .runtime
+                    .message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                let player = if origin_chain_id == duel.challenger.chain_id {
+                    duel.challenger
+                } else if origin_chain_id == duel.opponent.chain_id {
+                    duel.opponent
+                } else {
+                    return;
+                };
+                let Some(metrics) =
+                    self.duel_solution_metrics(duel.puzzle_id, &board, player.owner)
+                else {
+                    log::trace!("Ignoring invalid duel solution");
+                    return;
+                };
+                let solution = duel.solution_mut(origin_chain_id);
+                if solution.is_some() {
+                    return;
+                }
+                *solution = Some(DuelSolution {
+                    board,
+                    timestamp,
+                    metrics,
+                });
+                // A solution of the other player shows that the duel was accepted, even if
+                // the acceptance was not received yet.
+                duel.status = DuelStatus::Accepted;
+                self.store_duel(duel).await;
+            }
+            Message::ImmigrationGameCreated { game } => {
+                let origin_chain_id = self
+                    .runtime
+                    .message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                if game.id.chain_id != origin_chain_id
+                    || game.first_player.chain_id != origin_chain_id
+                    || self
+                        .state
+                        .immigration_games
+                        .contains_key(&game.id)
+                        .await
+                        .unwrap()
+                {
+                    log::trace!("Ignoring invalid or duplicate game");
+                    return;
+                }
+                self.state.immigration_games.insert(&game.id, game).unwrap();
+            }
+            Message::ImmigrationTurnPlayed { game_id, cells } => {
+                let origin_chain_id = self
+                    .runtime
+                    .message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                let Some(game) = self
+                    .state
+                    .immigration_games
+                    .get_mut(&game_id)
+                    .await
+                    .unwrap()
+                else {
+                    return;
+                };
+                let color = if origin_chain_id == game.first_player.chain_id {
+                    PlayerColor::First
+                } else if origin_chain_id == game.second_player.chain_id {
+                    PlayerColor::Second
+                } else {
+                    return;
+                };
+                if let Err(error) = game.game.play(color, &cells) {
+                    log::trace!("Ignoring invalid turn: {error}");
+                }
+            }
+            Message::PuzzlePublished {
+                puzzle_id,
+                solution_hash,
+                timestamp,
+            } => {
+                let Some(creator) = self.runtime.authenticated_signer() else {
+                    log::trace!("Ignoring unauthenticated published puzzle");
+                    return;
+                };
+                if self.is_rate_limited(creator).await {
+                    return;
+                }
+                if self
+                    .state
+                    .verified_puzzles
+                    .contains_key(&puzzle_id)
+                    .await
+                    .unwrap()
+                {
+                    log::trace!("Ignoring puzzle published twice");
+                    return;
+                }
+                let verified_puzzle = VerifiedPuzzle {
+                    creator,
+                    solution_hash,
+                    published_at: timestamp,
+                };
+                self.state
+                    .verified_puzzles
+                    .insert(&puzzle_id, verified_puzzle)
+                    .unwrap();
+                if self
+                    .state
+                    .registered_puzzles
+                    .contains(&puzzle_id)
+                    .await
+                    .unwrap()
+                {
+                    self.credit_creator(creator).await;
+                }
+            }
+            Message::PuzzleVote { puzzle_id, vote } => {
+                let voter = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Votes must be authenticated");
+                if !vote.is_valid() {
+                    log::trace!("Ignoring invalid vote");
+                    return;
+                }
+                if self.is_rate_limited(voter).await {
+                    return;
+                }
+                let has_solved = match self
+                    .state
+                    .reported_solutions
+                    .try_load_entry(&voter)
+                    .await
+                    .unwrap()
+                {
+                    Some(solved_puzzles) => solved_puzzles.contains_key(&puzzle_id).await.unwrap(),
+                    None => false,
+                };
+                if !has_solved {
+                    log::trace!("Ignoring vote of an owner not credited for the puzzle");
+                    return;
+                }
+                let voters = self
+                    .state
+                    .puzzle_voters
+                    .load_entry_mut(&puzzle_id)
+                    .await
+                    .unwrap();
+                if voters.contains(&voter).await.unwrap() {
+                    log::trace!("Ignoring second vote of an owner");
+                    return;
+                }
+                voters.insert(&voter).unwrap();
+                self.state
+                    .puzzle_votes
+                    .get_mut_or_default(&puzzle_id)
+                    .await
+                    .unwrap()
+                    .add(&vote);
+            }
+            Message::TeamRequest { name, request } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Team requests must be authenticated");
+                self.handle_team_request(owner, name, request).await;
+            }
+            Message::SolutionOutcome {
+                puzzle_id,
+                timestamp,
+                outcome,
+            } => {
+                let scoring_chain_id = self
+                    .runtime
+                    .message_origin_chain_id()
+                    .expect("Incoming message must have an origin");
+                let Some(solution) = self.state.solutions.get_mut(&puzzle_id).await.unwrap() else {
+                    return;
+                };
+                if solution.timestamp != timestamp {
+                    log::trace!("Ignoring outcome of a previous submission");
+                    return;
+                }
+                for status in &mut solution.scoring_statuses {
+                    if status.chain_id == scoring_chain_id {
+                        status.outcome = Some(outcome);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl GolChallengeContract {
+    /// Returns the given owner, or else the authenticated signer.
+    fn owner_or_signer(&mut self, owner: Option<AccountOwner>) -> AccountOwner {
+        owner.unwrap_or_else(|| {
+            self.runtime
+                .authenticated_signer()
+                .expect("Operation must have an owner or be authenticated.")
+        })
+    }
+
+    /// Checks that the owner credited for a solution is the authenticated owner, or else
+    /// that they signed a consent to this submission, whose nonce is then used up.
+    async fn check_consent(
+        &mut self,
+        owner: AccountOwner,
+        consent: Option<OwnerConsent>,
+        puzzle_id: DataBlobHash,
+        board: &Board,
+        scoring_chain_ids: &[ChainId],
+    ) {
+        if self.runtime.authenticated_signer() == Some(owner) {
+            return;
+        }
+        let consent = consent.expect("Crediting another owner requires their consent");
+        let message = SubmissionConsent {
             puzzle_id,
+            board_hash: board_hash(board),
+            scoring_chain_ids: scoring_chain_ids.to_vec(),
+            chain_id: self.runtime.chain_id(),
+            application_id: self.runtime.application_id().forget_abi(),
+            nonce: consent.nonce,
+        };
+        assert_eq!(
+            consent.signature.owner(),
+            owner,
+            "Consent must be signed by the credited owner"
+        );
+        consent
+            .signature
+            .verify(&message)
+            .expect("Invalid consent signature");
+        assert!(
+            !self
+                .state
+                .used_consents
+                .contains(&(owner, consent.nonce))
+                .await
+                .unwrap(),
+            "Consent was already used"
+        );
+        self.state
+            .used_consents
+            .insert(&(owner, consent.nonce))
+            .unwrap();
+    }
+
+    /// Verifies and stores a local solution, then reports it to the given scoring chains.
+    /// If a salt is given, the solution is revealed to scoring chains that received its
+    /// commitment instead.
+    async fn submit_solution(
+        &mut self,
+        puzzle_id: DataBlobHash,
+        board: Board,
+        owner: Option<AccountOwner>,
+        consent: Option<OwnerConsent>,
+        scoring_chain_ids: Vec<ChainId>,
+        salt: Option<String>,
+    ) {
+        let owner = self.owner_or_signer(owner);
+        self.check_consent(owner, consent, puzzle_id, &board, &scoring_chain_ids)
+            .await;
+        let puzzle = self
+            .read_puzzle(puzzle_id)
+            .into_puzzle_for(puzzle_id, &owner);
+        let steps = puzzle.check_solution(&board).expect("Invalid solution");
+        let timestamp = self.runtime.system_time();
+        let record = SolutionRecord {
+            live_cells: board.live_cell_count(),
+            board: board.clone(),
             timestamp,
             owner,
-        } = message;
-        let is_registered = self
+            steps,
+        };
+        self.state
+            .solution_history
+            .load_entry_mut(&puzzle_id)
+            .await
+            .unwrap()
+            .push(record.clone());
+        self.update_best_solution(puzzle_id, record).await;
+        let mut solution = Solution {
+            board,
+            timestamp,
+            owner,
+            scoring_statuses: Vec::new(),
+        };
+        self.report_solution(puzzle_id, &mut solution, scoring_chain_ids, salt);
+        self.state
+            .solutions
+            .insert(&puzzle_id, solution)
+            .expect("Store solution");
+        self.runtime.emit(
+            StreamName(SOLUTIONS_STREAM_NAME.to_vec()),
+            &GolEvent::SolutionSubmitted {
+                puzzle_id,
+                owner,
+                timestamp,
+            },
+        );
+    }
+
+    /// Reads and decodes the blob of a puzzle or a puzzle template.
+    fn read_puzzle(&mut self, puzzle_id: DataBlobHash) -> PuzzleBlob {
+        let puzzle_bytes = self.runtime.read_data_blob(puzzle_id);
+        PuzzleBlob::from_bytes(&puzzle_bytes).expect("Deserialize puzzle")
+    }
+
+    /// Returns a duel of this chain.
+    async fn duel(&mut self, duel_id: DuelId) -> Duel {
+        self.state
+            .duels
+            .get(&duel_id)
+            .await
+            .unwrap()
+            .expect("Unknown duel")
+    }
+
+    /// Verifies the duel solution of a player and returns its quality metrics.
+    fn duel_solution_metrics(
+        &mut self,
+        puzzle_id: DataBlobHash,
+        board: &Board,
+        owner: AccountOwner,
+    ) -> Option<QualityMetrics> {
+        let puzzle = self
+            .read_puzzle(puzzle_id)
+            .into_puzzle_for(puzzle_id, &owner);
+        let steps = puzzle.check_solution(board).ok()?;
+        Some(QualityMetrics::new(board, steps))
+    }
+
+    /// Stores a duel, after resolving it if both players submitted a solution.
+    async fn store_duel(&mut self, duel: Duel) {
+        let is_complete = duel.challenger_solution.is_some() && duel.opponent_solution.is_some();
+        if is_complete {
+            self.resolve_duel(duel).await;
+        } else {
+            self.state.duels.insert(&duel.id, duel).unwrap();
+        }
+    }
+
+    /// Decides the outcome of a duel from the solutions known to this chain, as timed by
+    /// this chain, and records it. Each chain decides on its own, so the chains of the two
+    /// players may disagree when the solutions are close in time.
+    async fn resolve_duel(&mut self, mut duel: Duel) {
+        let outcome = decide_duel(
+            duel.metric,
+            duel.challenger_solution
+                .as_ref()
+                .map(|solution| (solution.timestamp, &solution.metrics)),
+            duel.opponent_solution
+                .as_ref()
+                .map(|solution| (solution.timestamp, &solution.metrics)),
+        );
+        duel.status = DuelStatus::Resolved;
+        duel.outcome = Some(outcome);
+        self.record_duel(&duel, outcome).await;
+        self.state.duels.insert(&duel.id, duel).unwrap();
+    }
+
+    /// Updates the duel records of both players with the outcome of a duel.
+    async fn record_duel(&mut self, duel: &Duel, outcome: DuelOutcome) {
+        let players = [(duel.challenger.owner, true), (duel.opponent.owner, false)];
+        for (owner, is_challenger) in players {
+            let record = self
+                .state
+                .duel_records
+                .get_mut_or_default(&owner)
+                .await
+                .unwrap();
+            *record.result_mut(outcome, is_challenger) += 1;
+        }
+    }
+
+    /// Replaces the best solutions of the owner of a local solution, for each criterion,
+    /// if the new solution is better.
+    async fn update_best_solution(&mut self, puzzle_id: DataBlobHash, record: SolutionRecord) {
+        let best_solutions = self
             .state
-            .registered_puzzles
-            .contains(&puzzle_id)
+            .best_solutions
+            .load_entry_mut(&record.owner)
             .await
             .unwrap();
-        if is_registered {
-            let map = self
+        for criterion in SolutionCriterion::ALL {
+            let key = (puzzle_id, criterion);
+            if let Some(best) = best_solutions.get(&key).await.unwrap() {
+                if !record.is_better_than(&best, criterion) {
+                    continue;
+                }
+            }
+            best_solutions.insert(&key, record.clone()).unwrap();
+        }
+    }
+
+    /// Reports a local solution to the given scoring chains and marks it as pending on
+    /// each of them. If a salt is given, the solution is revealed instead, for scoring
+    /// chains that received its commitment.
+    fn report_solution(
+        &mut self,
+        puzzle_id: DataBlobHash,
+        solution: &mut Solution,
+        scoring_chain_ids: Vec<ChainId>,
+        salt: Option<String>,
+    ) {
+        for chain_id in scoring_chain_ids {
+            let message = match &salt {
+                Some(salt) => Message::SolutionReveal {
+                    puzzle_id,
+                    board: solution.board.clone(),
+                    salt: salt.clone(),
+                    timestamp: solution.timestamp,
+                    owner: solution.owner,
+                },
+                None => Message::SolutionReport {
+                    puzzle_id,
+                    board: solution.board.clone(),
+                    timestamp: solution.timestamp,
+                    owner: solution.owner,
+                },
+            };
+            self.runtime.prepare_message(message).send_to(chain_id);
+            solution
+                .scoring_statuses
+                .retain(|status| status.chain_id != chain_id);
+            solution.scoring_statuses.push(ScoringStatus {
+                chain_id,
+                outcome: None,
+            });
+        }
+    }
+
+    /// Checks the rate limits of the origin chain of an incoming message and of the given
+    /// owner. Records the message if it is allowed, otherwise counts the rejection.
+    async fn is_rate_limited(&mut self, owner: AccountOwner) -> bool {
+        let chain_id = self
+            .runtime
+            .message_origin_chain_id()
+            .expect("Incoming message must have an origin");
+        let now = self.runtime.system_time();
+        let limits = self.state.rate_limits.get().clone();
+        if let Some(limit) = &limits.per_chain {
+            let window = self
                 .state
-                .reported_solutions
-                .load_entry_mut(&owner)
+                .chain_windows
+                .get_mut_or_default(&chain_id)
                 .await
                 .unwrap();
-            map.insert(&puzzle_id, timestamp).unwrap();
-        } else {
-            log::trace!("Ignoring unregistered puzzle");
+            if !window.allows(limit, now) {
+                log::trace!("Rejecting message above the rate limit of chain {chain_id}");
+                *self
+                    .state
+                    .rate_limited_chains
+                    .get_mut_or_default(&chain_id)
+                    .await
+                    .unwrap() += 1;
+                return true;
+            }
+        }
+        if let Some(limit) = &limits.per_owner {
+            let window = self
+                .state
+                .owner_windows
+                .get_mut_or_default(&owner)
+                .await
+                .unwrap();
+            if !window.allows(limit, now) {
+                log::trace!("Rejecting message above the rate limit of owner {owner}");
+                *self
+                    .state
+                    .rate_limited_owners
+                    .get_mut_or_default(&owner)
+                    .await
+                    .unwrap() += 1;
+                return true;
+            }
+            window.record(limit, now);
+        }
+        if let Some(limit) = &limits.per_chain {
+            self.state
+                .chain_windows
+                .get_mut_or_default(&chain_id)
+                .await
+                .unwrap()
+                .record(limit, now);
         }
+        false
     }
 
-    async fn store(mut self) {
-        self.state.save().await.expect("Failed to save state");
+    /// Sends a team request of the authenticated owner to a scoring chain.
+    fn send_team_request(&mut self, name: String, request: TeamRequest, chain_id: ChainId) {
+        assert!(
+            self.runtime.authenticated_signer().is_some(),
+            "Team requests require an authenticated owner"
+        );
+        self.runtime
+            .prepare_message(Message::TeamRequest { name, request })
+            .with_authentication()
+            .send_to(chain_id);
     }
-}
 
-/// This implementation is only nonempty in the service.
-#[ComplexObject]
-impl GolChallengeState {}
+    /// Applies the team request of an owner. Invalid requests are ignored.
+    async fn handle_team_request(
+        &mut self,
+        owner: AccountOwner,
+        name: String,
+        request: TeamRequest,
+    ) {
+        let team = self.state.teams.get(&name).await.unwrap();
+        let has_team = self.state.owner_teams.contains_key(&owner).await.unwrap();
+        match (request, team) {
+            (TeamRequest::Create { policy }, None) if !has_team => {
+                let team = Team {
+                    creator: owner,
+                    policy,
+                    members: Vec::new(),
+                    invitations: Vec::new(),
+                    points: 0,
+                    solved_puzzles: 0,
+                };
+                self.add_team_member(name, team, owner).await;
+            }
+            (TeamRequest::Invite { member }, Some(mut team)) if team.creator == owner => {
+                if !team.members.contains(&member) && !team.invitations.contains(&member) {
+                    team.invitations.push(member);
+                }
+                self.state.teams.insert(&name, team).unwrap();
+            }
+            (TeamRequest::Join, Some(mut team))
+                if !has_team && team.invitations.contains(&owner) =>
+            {
+                team.invitations.retain(|invited| *invited != owner);
+                self.add_team_member(name, team, owner).await;
+            }
+            (TeamRequest::SetPolicy { policy }, Some(mut team)) if team.creator == owner => {
+                let old_score = team.policy.score(team.points, team.solved_puzzles);
+                team.policy = policy;
+                self.store_team(name, Some(old_score), team);
+            }
+            _ => log::trace!("Ignoring invalid team request"),
+        }
+    }
+
+    /// Adds a member to a team, together with the points and the puzzles they were
+    /// already credited for.
+    async fn add_team_member(&mut self, name: String, mut team: Team, owner: AccountOwner) {
+        let old_score =
+            (!team.members.is_empty()).then(|| team.policy.score(team.points, team.solved_puzzles));
+        team.members.push(owner);
+        self.state.owner_teams.insert(&owner, name.clone()).unwrap();
+        if let Some(score) = self.state.scores.owner_scores.get(&owner).await.unwrap() {
+            team.points = team.points.saturating_add(score.points);
+        }
+        let solved_puzzles = match self
+            .state
+            .reported_solutions
+            .try_load_entry(&owner)
+            .await
+            .unwrap()
+        {
+            Some(solved_puzzles) => solved_puzzles.indices().await.unwrap(),
+            None => Vec::new(),
+        };
+        let team_puzzles = self.state.team_puzzles.load_entry_mut(&name).await.unwrap();
+        for puzzle_id in solved_puzzles {
+            if !team_puzzles.contains(&puzzle_id).await.unwrap() {
+                team_puzzles.insert(&puzzle_id).unwrap();
+                team.solved_puzzles += 1;
+            }
+        }
+        self.store_team(name, old_score, team);
+    }
+
+    /// Credits the team of an owner, if any, for a new solution of the owner, or for
+    /// another achievement if no puzzle is given.
+    async fn credit_team(
+        &mut self,
+        owner: AccountOwner,
+        puzzle_id: Option<DataBlobHash>,
+        points: u64,
+    ) {
+        let Some(name) = self.state.owner_teams.get(&owner).await.unwrap() else {
+            return;
+        };
+        let mut team = self
+            .state
+            .teams
+            .get(&name)
+            .await
+            .unwrap()
+            .expect("Teams of members should exist");
+        let old_score = team.policy.score(team.points, team.solved_puzzles);
+        team.points = team.points.saturating_add(points);
+        if let Some(puzzle_id) = puzzle_id {
+            let team_puzzles = self.state.team_puzzles.load_entry_mut(&name).await.unwrap();
+            if !team_puzzles.contains(&puzzle_id).await.unwrap() {
+                team_puzzles.insert(&puzzle_id).unwrap();
+                team.solved_puzzles += 1;
+            }
+        }
+        self.store_team(name, Some(old_score), team);
+    }
+
+    /// Stores a team and moves it in the team leaderboard, given its previous score if
+    /// it was already ranked.
+    fn store_team(&mut self, name: String, old_score: Option<u64>, team: Team) {
+        if let Some(old_score) = old_score {
+            self.state
+                .sorted_teams
+                .remove(&TeamKey::new(name.clone(), old_score))
+                .unwrap();
+        }
+        let score = team.policy.score(team.points, team.solved_puzzles);
+        self.state
+            .sorted_teams
+            .insert(&TeamKey::new(name.clone(), score))
+            .unwrap();
+        self.state.teams.insert(&name, team).unwrap();
+    }
+
+    /// Sends the outcome of a reported solution back to the user chain.
+    fn send_outcome(
+        &mut self,
+        puzzle_id: DataBlobHash,
+        timestamp: Timestamp,
+        outcome: ScoringOutcome,
+    ) {
+        let origin_chain_id = self
+            .runtime
+            .message_origin_chain_id()
+            .expect("Incoming message must have an origin");
+        let message = Message::SolutionOutcome {
+            puzzle_id,
+            timestamp,
+            outcome,
+        };
+        self.runtime
+            .prepare_message(message)
+            .send_to(origin_chain_id);
+    }
+
+    /// Scores a solution reported by a user chain. Solutions revealed after a commitment
+    /// come with the number of owners credited before the commitment was received.
+    async fn score_solution(
+        &mut self,
+        puzzle_id: DataBlobHash,
+        board: Board,
+        timestamp: Timestamp,
+        owner: AccountOwner,
+        credited_before: Option<u32>,
+    ) -> ScoringOutcome {
+        self.claim_bounty(
+            puzzle_id,
+            &board,
+            owner,
+            timestamp,
+            credited_before.is_some(),
+        )
+        .await;
+        let is_registered = self
+            .state
+            .registered_puzzles
+            .contains(&puzzle_id)
+            .await
+            .unwrap();
+        if !is_registered {
+            log::trace!("Ignoring unregistered puzzle");
+            return ScoringOutcome::Ignored;
+        }
+        if !self.state.is_unlocked(owner, puzzle_id).await.unwrap() {
+            log::trace!("Ignoring solution to a locked puzzle");
+            return ScoringOutcome::Locked;
+        }
+        // Solutions to puzzles with a quality ranking are always verified, since the
+        // number of steps is needed, and so are solutions to puzzles with a token reward.
+        let ranking = self.state.puzzle_rankings.get(&puzzle_id).await.unwrap();
+        let has_reward = self
+            .state
+            .puzzle_rewards
+            .contains_key(&puzzle_id)
+            .await
+            .unwrap();
+        let must_verify =
+            *self.state.verify_reported_solutions.get() || ranking.is_some() || has_reward;
+        let steps = if must_verify {
+            let puzzle = self
+                .read_puzzle(puzzle_id)
+                .into_puzzle_for(puzzle_id, &owner);
+            match puzzle.check_solution(&board) {
+                Ok(steps) => Some(steps),
+                Err(error) => {
+                    log::trace!("Rejecting invalid solution: {error}");
+                    return ScoringOutcome::Rejected;
+                }
+            }
+        } else {
+            None
+        };
+        let mut meets_par = None;
+        if let (Some(ranking), Some(steps)) = (ranking, steps) {
+            let metrics = QualityMetrics::new(&board, steps);
+            meets_par = Some(ranking.meets_par(&metrics));
+            self.state
+                .quality_boards
+                .load_entry_mut(&puzzle_id)
+                .await
+                .unwrap()
+                .record(owner, metrics, ranking.metric)
+                .await;
+        }
+        let registered_puzzle = self.registered_puzzle(puzzle_id).await;
+        let outcome = match self
+            .credit_solution(
+                owner,
+                puzzle_id,
+                &registered_puzzle,
+                timestamp,
+                credited_before,
+            )
+            .await
+        {
+            Some(rank) => {
+                self.pay_reward(owner, puzzle_id, rank, timestamp).await;
+                ScoringOutcome::Credited
+            }
+            None => ScoringOutcome::Duplicate,
+        };
+        self.award_achievements(owner, puzzle_id, &registered_puzzle, meets_par)
+            .await;
+        self.credit_seasons(owner, puzzle_id, &registered_puzzle, timestamp)
+            .await;
+        outcome
+    }
+
+    /// Evaluates the achievements not yet earned by an owner after one of their solutions
+    /// to a registered puzzle was accepted, whether it was credited now or before, and
+    /// records the achievements earned.
+    async fn award_achievements(
+        &mut self,
+        owner: AccountOwner,
+        puzzle_id: DataBlobHash,
+        registered_puzzle: &RegisteredPuzzle,
+        meets_par: Option<bool>,
+    ) {
+        let earned = self
+            .state
+            .earned_achievements
+            .load_entry_mut(&owner)
+            .await
+            .unwrap();
+        let earned_names = earned.indices().await.unwrap();
+        let achievements = self
+            .state
+            .achievements
+            .index_values()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|(name, _)| !earned_names.contains(name))
+            .collect::<Vec<_>>();
+        if achievements.is_empty() {
+            return;
+        }
+        let credited_solution = self
+            .state
+            .reported_solutions
+            .load_entry_mut(&owner)
+            .await
+            .unwrap()
+            .get(&puzzle_id)
+            .await
+            .unwrap()
+            .expect("Accepted solutions should be credited");
+        let day = day_index(credited_solution.timestamp);
+        let facts = SolverFacts {
+            solved_counts: self
+                .state
+                .solved_counts
+                .get(&owner)
+                .await
+                .unwrap()
+                .unwrap_or_default(),
+            daily_solved_counts: self
+                .state
+                .daily_solved_counts
+                .get(&(owner, day))
+                .await
+                .unwrap()
+                .unwrap_or_default(),
+            registered_counts: DifficultyCounts(
+                self.state.registered_counts.index_values().await.unwrap(),
+            ),
+            difficulty: registered_puzzle.difficulty,
+            rank: credited_solution.rank,
+            meets_par,
+        };
+        let earned_at = self.runtime.system_time();
+        let earned = self
+            .state
+            .earned_achievements
+            .load_entry_mut(&owner)
+            .await
+            .unwrap();
+        for (name, achievement) in achievements {
+            if achievement.rule.is_met(&facts) {
+                earned.insert(&name, earned_at).unwrap();
+            }
+        }
+    }
+
+    /// Pays the bounty of a puzzle, if any, to the account of the owner on the chain that
+    /// reported a valid solution. Solutions are always verified, and the bounty must not
+    /// be expired when the solution is received. Until the reveal deadline of the last
+    /// commitment to the puzzle, only revealed solutions may claim the bounty, so that a
+    /// revealed board cannot be copied to claim it first.
+    async fn claim_bounty(
+        &mut self,
+        puzzle_id: DataBlobHash,
+        board: &Board,
+        owner: AccountOwner,
+        timestamp: Timestamp,
+        is_revealed: bool,
+    ) {
+        let Some(bounty) = self.state.bounties.get(&puzzle_id).await.unwrap() else {
+            return;
+        };
+        if self.runtime.system_time() >= bounty.expiry {
+            log::trace!("Bounty is expired");
+            return;
+        }
+        if !is_revealed {
+            let deadline = self.state.commit_deadlines.get(&puzzle_id).await.unwrap();
+            if deadline.is_some_and(|deadline| self.runtime.system_time() <= deadline) {
+                log::trace!("Bounty is reserved for the pending commitments");
+                return;
+            }
+        }
+        let puzzle = self
+            .read_puzzle(puzzle_id)
+            .into_puzzle_for(puzzle_id, &owner);
+        if let Err(error) = puzzle.check_solution(board) {
+            log::trace!("Invalid solution for bounty: {error}");
+            return;
+        }
+        let recipient = Account {
+            chain_id: self
+                .runtime
+                .message_origin_chain_id()
+                .expect("Incoming message must have an origin"),
+            owner,
+        };
+        let application_owner = self.application_owner();
+        self.runtime
+            .transfer(application_owner, recipient, bounty.amount);
+        self.state.bounties.remove(&puzzle_id).unwrap();
+        self.state.bounty_payouts.push(RewardPayout {
+            puzzle_id,
+            recipient,
+            amount: bounty.amount,
+            timestamp,
+        });
+    }
+
+    /// The account owner holding the tokens of the application, e.g. the reward pool.
+    fn application_owner(&mut self) -> AccountOwner {
+        AccountOwner::from(self.runtime.application_id().forget_abi())
+    }
+
+    /// Returns the catalog entry of a registered puzzle.
+    async fn registered_puzzle(&mut self, puzzle_id: DataBlobHash) -> RegisteredPuzzle {
+        self.state
+            .puzzle_catalog
+            .get(&puzzle_id)
+            .await
+            .unwrap()
+            .expect("Registered puzzles should have a catalog entry")
+    }
+
+    /// Credits an owner for a new solution to a registered puzzle and returns the rank
+    /// of the owner: one more than the number of owners credited before the solution was
+    /// received, or before its commitment was received if it was revealed. Owners credited
+    /// in between keep their rank, so a rank may be shared. Solutions already credited keep
+    /// their original timestamp and rank.
+    async fn credit_solution(
+        &mut self,
+        owner: AccountOwner,
+        puzzle_id: DataBlobHash,
+        registered_puzzle: &RegisteredPuzzle,
+        timestamp: Timestamp,
+        credited_before: Option<u32>,
+    ) -> Option<u32> {
+        let map = self
+            .state
+            .reported_solutions
+            .load_entry_mut(&owner)
+            .await
+            .unwrap();
+        if map.contains_key(&puzzle_id).await.unwrap() {
+            log::trace!("Ignoring duplicate solution");
+            return None;
+        }
+        let solver_count = self
+            .state
+            .solver_counts
+            .get_mut_or_default(&puzzle_id)
+            .await
+            .unwrap();
+        let rank = credited_before.unwrap_or(*solver_count) + 1;
+        *solver_count += 1;
+        self.state
+            .solve_logs
+            .load_entry_mut(&puzzle_id)
+            .await
+            .unwrap()
+            .push(SolveEvent {
+                owner,
+                timestamp,
+                rank,
+            });
+
+        let elapsed_micros = timestamp
+            .micros()
+            .saturating_sub(registered_puzzle.registered_at.micros());
+        let points = self.state.points_config.get().points(
+            registered_puzzle.difficulty,
+            elapsed_micros,
+            rank == 1,
+        );
+        let points = points.saturating_add(self.daily_points(owner, puzzle_id, timestamp).await);
+        let credited_solution = CreditedSolution {
+            timestamp,
+            rank,
+            points,
+        };
+        self.state
+            .reported_solutions
+            .load_entry_mut(&owner)
+            .await
+            .unwrap()
+            .insert(&puzzle_id, credited_solution)
+            .unwrap();
+        self.count_solution(owner, registered_puzzle.difficulty, timestamp)
+            .await;
+        self.state.scores.credit(owner, points, true).await;
+        self.credit_team(owner, Some(puzzle_id), points).await;
+        self.runtime.emit(
+            StreamName(SOLUTIONS_STREAM_NAME.to_vec()),
+            &GolEvent::SolutionScored {
+                puzzle_id,
+                owner,
+                timestamp,
+                rank,
+                points,
+            },
+        );
+        Some(rank)
+    }
+
+    /// Counts a solution credited to an owner for the achievements, by difficulty and by
+    /// day.
+    async fn count_solution(
+        &mut self,
+        owner: AccountOwner,
+        difficulty: Difficulty,
+        timestamp: Timestamp,
+    ) {
+        self.state
+            .solved_counts
+            .get_mut_or_default(&owner)
+            .await
+            .unwrap()
+            .increment(difficulty);
+        self.state
+            .daily_solved_counts
+            .get_mut_or_default(&(owner, day_index(timestamp)))
+            .await
+            .unwrap()
+            .increment(difficulty);
+    }
+
+    /// Credits the creator of a published puzzle, and their team, with the publication
+    /// points. Only registered puzzles are credited, so that creators cannot farm points by
+    /// publishing puzzles.
+    async fn credit_creator(&mut self, creator: AccountOwner) {
+        // The difficulty is chosen by the creator, so it does not determine the points.
+        let points = *self.state.publication_points.get();
+        if points > 0 {
+            self.state.scores.credit(creator, points, false).await;
+            self.credit_team(creator, None, points).await;
+        }
+    }
+
+    /// Credits the points of the solutions to a legacy puzzle registered again. The
+    /// solutions were ranked by the migration, but the difficulty of the puzzle was unknown.
+    /// The time of the first registration is unknown as well, so there is no time bonus.
+    async fn credit_legacy_solvers(&mut self, puzzle_id: DataBlobHash, difficulty: Difficulty) {
+        let Some(solve_log) = self
+            .state
+            .solve_logs
+            .try_load_entry(&puzzle_id)
+            .await
+            .unwrap()
+        else {
+            return;
+        };
+        let events = solve_log.read(..).await.unwrap();
+        for event in events {
+            let points =
+                self.state
+                    .points_config
+                    .get()
+                    .points(difficulty, u64::MAX, event.rank == 1);
+            let solved_puzzles = self
+                .state
+                .reported_solutions
+                .load_entry_mut(&event.owner)
+                .await
+                .unwrap();
+            let Some(mut credited_solution) = solved_puzzles.get(&puzzle_id).await.unwrap() else {
+                continue;
+            };
+            credited_solution.points = points;
+            solved_puzzles
+                .insert(&puzzle_id, credited_solution)
+                .unwrap();
+            self.count_solution(event.owner, difficulty, event.timestamp)
+                .await;
+            self.state.scores.credit(event.owner, points, false).await;
+            self.credit_team(event.owner, None, points).await;
+        }
+    }
+
+    /// Updates the streak of an owner if a new solution solves the puzzle of the day of
+    /// its timestamp, and returns the streak points.
+    async fn daily_points(
+        &mut self,
+        owner: AccountOwner,
+        puzzle_id: DataBlobHash,
+        timestamp: Timestamp,
+    ) -> u64 {
+        let day = day_index(timestamp);
+        if self.state.daily_puzzles.get(&day).await.unwrap() != Some(puzzle_id) {
+            return 0;
+        }
+        let streak = self
+            .state
+            .daily_streaks
+            .get_mut_or_default(&owner)
+            .await
+            .unwrap()
+            .record(day);
+        self.state.daily_config.get().points(streak)
+    }
+
+    /// Pays the token reward of a puzzle, if any, to the account of the owner on the
+    /// chain that reported the solution. The payment is limited by the reward pool.
+    async fn pay_reward(
+        &mut self,
+        owner: AccountOwner,
+        puzzle_id: DataBlobHash,
+        rank: u32,
+        timestamp: Timestamp,
+    ) {
+        let Some(reward) = self.state.puzzle_rewards.get(&puzzle_id).await.unwrap() else {
+            return;
+        };
+        let balance = *self.state.reward_pool_balance.get();
+        let amount = reward.amount_for_rank(rank).min(balance);
+        if amount == Amount::ZERO {
+            return;
+        }
+        let recipient = Account {
+            chain_id: self
+                .runtime
+                .message_origin_chain_id()
+                .expect("Incoming message must have an origin"),
+            owner,
+        };
+        let application_owner = self.application_owner();
+        self.runtime.transfer(application_owner, recipient, amount);
+        self.state
+            .reward_pool_balance
+            .set(balance.saturating_sub(amount));
+        self.state.reward_payouts.push(RewardPayout {
+            puzzle_id,
+            recipient,
+            amount,
+            timestamp,
+        });
+    }
+
+    /// Credits an owner in every season running at the time of the solution and
+    /// including the puzzle. Seasons award the points of the difficulty and the time
+    /// bonus, counted from the start of the season or the registration of the puzzle.
+    async fn credit_seasons(
+        &mut self,
+        owner: AccountOwner,
+        puzzle_id: DataBlobHash,
+        registered_puzzle: &RegisteredPuzzle,
+        timestamp: Timestamp,
+    ) {
+        for name in self.state.seasons.indices().await.unwrap() {
+            let season = self.state.seasons.get(&name).await.unwrap().unwrap();
+            if !season.contains(timestamp) || !season.puzzle_ids.contains(&puzzle_id) {
+                continue;
+            }
+            let solutions = self
+                .state
+                .season_solutions
+                .load_entry_mut(&name)
+                .await
+                .unwrap();
+            if solutions.contains(&(owner, puzzle_id)).await.unwrap() {
+                continue;
+            }
+            solutions.insert(&(owner, puzzle_id)).unwrap();
+
+            let start = season.start.max(registered_puzzle.registered_at);
+            let elapsed_micros = timestamp.micros().saturating_sub(start.micros());
+            let points = self.state.points_config.get().points(
+                registered_puzzle.difficulty,
+                elapsed_micros,
+                false,
+            );
+            self.state
+                .season_scores
+                .load_entry_mut(&name)
+                .await
+                .unwrap()
+                .credit(owner, points, true)
+                .await;
+        }
+    }
+}
+
+impl Duel {
+    /// The solution slot of the player of the given chain.
+    fn solution_mut(&mut self, chain_id: ChainId) -> &mut Option<DuelSolution> {
+        if chain_id == self.challenger.chain_id {
+            &mut self.challenger_solution
+        } else {
+            &mut self.opponent_solution
+        }
+    }
+}
+
+impl SolutionCommit {
+    /// The time after which the solution may no longer be revealed.
+    fn deadline(&self) -> Timestamp {
+        Timestamp::from(
+            self.timestamp
+                .micros()
+                .saturating_add(SOLUTION_COMMIT_TTL_MICROS),
+        )
+    }
+}
+
+impl DuelRecord {
+    /// The counter of the result of a player given the outcome of a duel.
+    fn result_mut(&mut self, outcome: DuelOutcome, is_challenger: bool) -> &mut u32 {
+        match (outcome, is_challenger) {
+            (DuelOutcome::Draw, _) => &mut self.draws,
+            (DuelOutcome::ChallengerWon, true) | (DuelOutcome::OpponentWon, false) => {
+                &mut self.wins
+            }
+            (DuelOutcome::ChallengerWon, false) | (DuelOutcome::OpponentWon, true) => {
+                &mut self.losses
+            }
+        }
+    }
+}
+
+impl SolutionRecord {
+    /// Whether this solution is strictly better than another one.
+    fn is_better_than(&self, other: &SolutionRecord, criterion: SolutionCriterion) -> bool {
+        let key = |record: &SolutionRecord| match criterion {
+            SolutionCriterion::FewestCells => {
+                (record.live_cells, u32::from(record.steps), record.timestamp)
+            }
+            SolutionCriterion::FewestSteps => {
+                (u32::from(record.steps), record.live_cells, record.timestamp)
+            }
+            SolutionCriterion::Earliest => (0, 0, record.timestamp),
+        };
+        key(self) < key(other)
+    }
+}
+
+impl Leaderboard {
+    /// Adds points to the score of an owner and updates the sorted index. The points are
+    /// for a new solution unless `is_solution` is false, e.g. for publishing a puzzle.
+    async fn credit(&mut self, owner: AccountOwner, points: u64, is_solution: bool) {
+        let mut score = match self.owner_scores.get(&owner).await.unwrap() {
+            Some(score) => {
+                self.sorted_scores
+                    .remove(&LeaderboardKey::new(owner, score.points))
+                    .unwrap();
+                score
+            }
+            None => OwnerScore::default(),
+        };
+        score.points = score.points.saturating_add(points);
+        if is_solution {
+            score.solved_puzzles += 1;
+        }
+        self.sorted_scores
+            .insert(&LeaderboardKey::new(owner, score.points))
+            .unwrap();
+        self.owner_scores.insert(&owner, score).unwrap();
+    }
+}
+
+impl QualityBoard {
+    /// Records a solution of an owner if it is better than the previous one.
+    async fn record(
+        &mut self,
+        owner: AccountOwner,
+        metrics: QualityMetrics,
+        metric: QualityMetric,
+    ) {
+        let value = metrics.value(metric);
+        if let Some(previous) = self.owner_metrics.get(&owner).await.unwrap() {
+            let previous_value = previous.value(metric);
+            if !metric.is_better(value, previous_value) {
+                return;
+            }
+            self.sorted_owners
+                .remove(&QualityKey::new(owner, metric, previous_value))
+                .unwrap();
+        }
+        self.sorted_owners
+            .insert(&QualityKey::new(owner, metric, value))
+            .unwrap();
+        self.owner_metrics.insert(&owner, metrics).unwrap();
+    }
+}
+
+/// This implementation is only nonempty in the service.
+#[ComplexObject]
+impl GolChallengeState {}
+
+/// This implementation is only nonempty in the service.
+#[ComplexObject]
+impl Leaderboard {}
+
+#[cfg(test)]
+mod tests {
+    use gol_challenge::{
+        achievements::{Achievement, AchievementKind, AchievementRule},
+        game::{Condition, Difficulty, Puzzle},
+        points::PointsConfig,
+        quality::QualityRanking,
+        templates::RankedPuzzle,
+    };
+    use linera_sdk::{
+        linera_base_types::{AccountSecretKey, ApplicationId, Ed25519SecretKey},
+        util::BlockingWait,
+    };
+
+    use super::*;
+
+    /// A scoring chain with a registered puzzle, awarding a bonus to its first solver.
+    fn scoring_contract(puzzle_id: DataBlobHash) -> GolChallengeContract {
+        let runtime = ContractRuntime::new()
+            .with_chain_id(ChainId(CryptoHash::test_hash("scoring chain")))
+            .with_application_id(ApplicationId::new(CryptoHash::test_hash("app")).with_abi())
+            .with_system_time(Timestamp::from(0));
+        let mut contract = GolChallengeContract::load(runtime).blocking_wait();
+        contract
+            .state
+            .registered_puzzles
+            .insert(&puzzle_id)
+            .unwrap();
+        let registered_puzzle = RegisteredPuzzle {
+            title: "Block".to_string(),
+            difficulty: Difficulty::Easy,
+            size: 4,
+            registered_at: Timestamp::from(0),
+        };
+        contract
+            .state
+            .puzzle_catalog
+            .insert(&puzzle_id, registered_puzzle)
+            .unwrap();
+        contract.state.points_config.set(PointsConfig {
+            first_solver_bonus_points: 10,
+            ..PointsConfig::default()
+        });
+        contract
+    }
+
+    /// Executes a message received from a user chain at the given time, authenticated by
+    /// the given signer, if any.
+    fn receive(
+        contract: &mut GolChallengeContract,
+        origin: ChainId,
+        signer: Option<AccountOwner>,
+        now: u64,
+        message: Message,
+    ) {
+        contract.runtime.set_message_origin_chain_id(origin);
+        contract.runtime.set_authenticated_signer(signer);
+        contract.runtime.set_system_time(Timestamp::from(now));
+        contract.execute_message(message).blocking_wait();
+    }
+
+    /// The points of an owner on the leaderboard of a scoring chain.
+    fn points(contract: &GolChallengeContract, owner: AccountOwner) -> u64 {
+        contract
+            .state
+            .scores
+            .owner_scores
+            .get(&owner)
+            .blocking_wait()
+            .unwrap()
+            .map_or(0, |score| score.points)
+    }
+
+    /// The owners credited for a puzzle, with the timestamps and the ranks of their
+    /// solutions, in order of crediting.
+    fn solve_events(
+        contract: &GolChallengeContract,
+        puzzle_id: DataBlobHash,
+    ) -> Vec<(AccountOwner, Timestamp, u32)> {
+        let Some(solve_log) = contract
+            .state
+            .solve_logs
+            .try_load_entry(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+        else {
+            return Vec::new();
+        };
+        solve_log
+            .read(..)
+            .blocking_wait()
+            .unwrap()
+            .into_iter()
+            .map(|event| (event.owner, event.timestamp, event.rank))
+            .collect()
+    }
+
+    /// A puzzle solved by a block of four live cells, and its solution.
+    fn block_puzzle() -> (Puzzle, Board) {
+        let puzzle = Puzzle {
+            title: "Block".to_string(),
+            summary: "Make a block".to_string(),
+            difficulty: Difficulty::Easy,
+            size: 4,
+            metadata: String::new(),
+            minimal_steps: 1,
+            maximal_steps: 1,
+            enforce_initial_conditions: false,
+            is_strict: false,
+            initial_conditions: Vec::new(),
+            final_conditions: vec![Condition::TestRectangle {
+                x_range: 1..3,
+                y_range: 1..3,
+                min_live_count: 4,
+                max_live_count: 4,
+            }],
+        };
+        let cells = [(1, 1), (2, 1), (1, 2), (2, 2)];
+        let board = Board::with_live_cells(4, cells.map(|(x, y)| Position { x, y }).to_vec());
+        (puzzle, board)
+    }
+
+    /// Lets the contract read the blob of a puzzle once.
+    fn expect_blob_read(contract: &mut GolChallengeContract, puzzle_id: DataBlobHash) {
+        let (puzzle, _) = block_puzzle();
+        let bytes = bcs::to_bytes(&puzzle).unwrap();
+        contract
+            .runtime
+            .add_expected_read_data_blob_requests(puzzle_id, bytes);
+    }
+
+    #[test]
+    fn revealed_solutions_are_ranked_from_their_commitment() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let attacker = AccountOwner::from(CryptoHash::test_hash("attacker"));
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let copier = AccountOwner::from(CryptoHash::test_hash("copier"));
+        let late_solver = AccountOwner::from(CryptoHash::test_hash("late solver"));
+        let attacker_chain_id = ChainId(CryptoHash::test_hash("attacker chain"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let copier_chain_id = ChainId(CryptoHash::test_hash("copier chain"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let commitment = solution_commitment(&board, &solver, "secret");
+
+        // A commitment that is never revealed does not take a rank.
+        let message = Message::SolutionCommit {
+            puzzle_id,
+            commitment: "garbage".to_string(),
+        };
+        receive(&mut contract, attacker_chain_id, Some(attacker), 5, message);
+        let message = Message::SolutionCommit {
+            puzzle_id,
+            commitment: commitment.clone(),
+        };
+        receive(&mut contract, solver_chain_id, Some(solver), 10, message);
+        // The board is copied from the reveal and reported before the reveal is received.
+        let report = |owner, timestamp| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner,
+        };
+        receive(&mut contract, copier_chain_id, None, 30, report(copier, 25));
+        // A reveal with another salt does not match the commitment.
+        let reveal = |salt: &str| Message::SolutionReveal {
+            puzzle_id,
+            board: board.clone(),
+            salt: salt.to_string(),
+            timestamp: Timestamp::from(20),
+            owner: solver,
+        };
+        receive(&mut contract, solver_chain_id, None, 35, reveal("wrong"));
+        let key = (solver, commitment);
+        assert!(contract
+            .state
+            .solution_commits
+            .contains_key(&key)
+            .blocking_wait()
+            .unwrap());
+        receive(&mut contract, solver_chain_id, None, 40, reveal("secret"));
+        receive(
+            &mut contract,
+            copier_chain_id,
+            None,
+            50,
+            report(late_solver, 45),
+        );
+
+        // The copy was credited before the reveal, so it keeps its rank, but the revealed
+        // solution is not ranked after it.
+        assert_eq!(
+            solve_events(&contract, puzzle_id),
+            [
+                (copier, Timestamp::from(25), 1),
+                (solver, Timestamp::from(10), 1),
+                (late_solver, Timestamp::from(45), 3),
+            ]
+        );
+        assert_eq!(points(&contract, solver), 12);
+        assert_eq!(points(&contract, late_solver), 2);
+        // The commitment was consumed by the reveal.
+        assert!(!contract
+            .state
+            .solution_commits
+            .contains_key(&key)
+            .blocking_wait()
+            .unwrap());
+    }
+
+    #[test]
+    fn commitments_are_bound_to_their_owner() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let attacker = AccountOwner::from(CryptoHash::test_hash("attacker"));
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let attacker_chain_id = ChainId(CryptoHash::test_hash("attacker chain"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let commitment = solution_commitment(&board, &solver, "secret");
+        let commit = || Message::SolutionCommit {
+            puzzle_id,
+            commitment: commitment.clone(),
+        };
+
+        // The commitment is copied from the block of the solver and received first.
+        receive(
+            &mut contract,
+            attacker_chain_id,
+            Some(attacker),
+            5,
+            commit(),
+        );
+        receive(&mut contract, solver_chain_id, Some(solver), 10, commit());
+        let message = Message::SolutionReveal {
+            puzzle_id,
+            board,
+            salt: "secret".to_string(),
+            timestamp: Timestamp::from(10),
+            owner: solver,
+        };
+        receive(&mut contract, solver_chain_id, None, 20, message);
+        assert_eq!(
+            solve_events(&contract, puzzle_id),
+            [(solver, Timestamp::from(10), 1)]
+        );
+    }
+
+    #[test]
+    fn expired_commitments_are_scored_as_reports() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let other_solver = AccountOwner::from(CryptoHash::test_hash("other solver"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let commitment = solution_commitment(&board, &solver, "secret");
+
+        let message = Message::SolutionCommit {
+            puzzle_id,
+            commitment,
+        };
+        receive(&mut contract, solver_chain_id, Some(solver), 10, message);
+        let message = Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(20),
+            owner: other_solver,
+        };
+        receive(&mut contract, solver_chain_id, None, 20, message);
+        let now = 10 + SOLUTION_COMMIT_TTL_MICROS + 1;
+        let message = Message::SolutionReveal {
+            puzzle_id,
+            board,
+            salt: "secret".to_string(),
+            timestamp: Timestamp::from(now - 5),
+            owner: solver,
+        };
+        receive(&mut contract, solver_chain_id, None, now, message);
+        assert_eq!(
+            solve_events(&contract, puzzle_id),
+            [
+                (other_solver, Timestamp::from(20), 1),
+                (solver, Timestamp::from(now - 5), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn expired_commitments_do_not_block_bounties() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let (_, board) = block_puzzle();
+        let attacker = AccountOwner::from(CryptoHash::test_hash("attacker"));
+        let poster = AccountOwner::from(CryptoHash::test_hash("poster"));
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let attacker_chain_id = ChainId(CryptoHash::test_hash("attacker chain"));
+        let poster_chain_id = ChainId(CryptoHash::test_hash("poster chain"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let amount = Amount::from_tokens(5);
+        let application_owner = contract.application_owner();
+        contract
+            .runtime
+            .set_owner_balance(application_owner, amount);
+
+        let message = Message::BountyPosted {
+            puzzle_id,
+            amount,
+            expiry: Timestamp::from(u64::MAX),
+            poster,
+        };
+        receive(&mut contract, poster_chain_id, None, 0, message);
+        let message = Message::SolutionCommit {
+            puzzle_id,
+            commitment: "garbage".to_string(),
+        };
+        receive(
+            &mut contract,
+            attacker_chain_id,
+            Some(attacker),
+            10,
+            message,
+        );
+        let report = |timestamp| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner: solver,
+        };
+        // Until the commitment expires, only a revealed solution may claim the bounty.
+        receive(&mut contract, solver_chain_id, None, 20, report(20));
+        assert!(contract
+            .state
+            .bounties
+            .contains_key(&puzzle_id)
+            .blocking_wait()
+            .unwrap());
+
+        let now = 10 + SOLUTION_COMMIT_TTL_MICROS + 1;
+        expect_blob_read(&mut contract, puzzle_id);
+        receive(&mut contract, solver_chain_id, None, now, report(now));
+        assert!(!contract
+            .state
+            .bounties
+            .contains_key(&puzzle_id)
+            .blocking_wait()
+            .unwrap());
+        let recipient = Account {
+            chain_id: solver_chain_id,
+            owner: solver,
+        };
+        assert_eq!(
+            contract.runtime.outgoing_transfers().get(&recipient),
+            Some(&amount)
+        );
+    }
+
+    /// Whether a scoring chain holds a bounty on the puzzle.
+    fn has_bounty(contract: &GolChallengeContract, puzzle_id: DataBlobHash) -> bool {
+        contract
+            .state
+            .bounties
+            .contains_key(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+    }
+
+    #[test]
+    fn bounties_are_escrowed_on_the_scoring_chain() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let (_, board) = block_puzzle();
+        let poster = AccountOwner::from(CryptoHash::test_hash("poster"));
+        let scoring_chain_id = ChainId(CryptoHash::test_hash("scoring chain"));
+        let amount = Amount::from_tokens(5);
+        let mut contract = user_contract(puzzle_id, &board, poster, poster);
+        contract.runtime.set_owner_balance(poster, amount);
+
+        expect_blob_read(&mut contract, puzzle_id);
+        let operation = Operation::PostBounty {
+            puzzle_id,
+            amount,
+            expiry: Timestamp::from(100),
+            scoring_chain_id,
+        };
+        contract.execute_operation(operation).blocking_wait();
+        let escrow = Account {
+            chain_id: scoring_chain_id,
+            owner: contract.application_owner(),
+        };
+        assert_eq!(
+            contract.runtime.outgoing_transfers().get(&escrow),
+            Some(&amount)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounty expiry is in the past")]
+    fn expired_bounties_are_not_posted() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let (_, board) = block_puzzle();
+        let poster = AccountOwner::from(CryptoHash::test_hash("poster"));
+        let mut contract = user_contract(puzzle_id, &board, poster, poster);
+        contract.runtime.set_system_time(Timestamp::from(100));
+
+        expect_blob_read(&mut contract, puzzle_id);
+        let operation = Operation::PostBounty {
+            puzzle_id,
+            amount: Amount::from_tokens(5),
+            expiry: Timestamp::from(100),
+            scoring_chain_id: ChainId(CryptoHash::test_hash("scoring chain")),
+        };
+        contract.execute_operation(operation).blocking_wait();
+    }
+
+    #[test]
+    fn bounties_are_claimed_once() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let (_, board) = block_puzzle();
+        let poster = AccountOwner::from(CryptoHash::test_hash("poster"));
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let other_solver = AccountOwner::from(CryptoHash::test_hash("other solver"));
+        let poster_chain_id = ChainId(CryptoHash::test_hash("poster chain"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let amount = Amount::from_tokens(5);
+        let application_owner = contract.application_owner();
+        contract
+            .runtime
+            .set_owner_balance(application_owner, amount);
+
+        let message = Message::BountyPosted {
+            puzzle_id,
+            amount,
+            expiry: Timestamp::from(100),
+            poster,
+        };
+        receive(&mut contract, poster_chain_id, None, 0, message);
+        assert!(has_bounty(&contract, puzzle_id));
+        let report = |owner, timestamp| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner,
+        };
+        expect_blob_read(&mut contract, puzzle_id);
+        receive(&mut contract, solver_chain_id, None, 10, report(solver, 10));
+        assert!(!has_bounty(&contract, puzzle_id));
+
+        // The bounty was paid out, so a later solution is credited without a payout.
+        receive(
+            &mut contract,
+            solver_chain_id,
+            None,
+            20,
+            report(other_solver, 20),
+        );
+        let recipient = |owner| Account {
+            chain_id: solver_chain_id,
+            owner,
+        };
+        let transfers = contract.runtime.outgoing_transfers();
+        assert_eq!(transfers.get(&recipient(solver)), Some(&amount));
+        assert_eq!(transfers.get(&recipient(other_solver)), None);
+        let payouts = contract
+            .state
+            .bounty_payouts
+            .read(..)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts[0].recipient, recipient(solver));
+        assert_eq!(
+            solve_events(&contract, puzzle_id),
+            [
+                (solver, Timestamp::from(10), 1),
+                (other_solver, Timestamp::from(20), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn bounties_are_reclaimed_after_expiry() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let poster = AccountOwner::from(CryptoHash::test_hash("poster"));
+        let attacker = AccountOwner::from(CryptoHash::test_hash("attacker"));
+        let poster_chain_id = ChainId(CryptoHash::test_hash("poster chain"));
+        let attacker_chain_id = ChainId(CryptoHash::test_hash("attacker chain"));
+        let amount = Amount::from_tokens(5);
+        let application_owner = contract.application_owner();
+        contract
+            .runtime
+            .set_owner_balance(application_owner, amount);
+
+        let message = Message::BountyPosted {
+            puzzle_id,
+            amount,
+            expiry: Timestamp::from(100),
+            poster,
+        };
+        receive(&mut contract, poster_chain_id, None, 0, message);
+        let reclaim = || Message::BountyReclaim { puzzle_id };
+        // The poster cannot reclaim the bounty before it expires.
+        receive(&mut contract, poster_chain_id, Some(poster), 50, reclaim());
+        assert!(has_bounty(&contract, puzzle_id));
+        // Nobody but the poster can reclaim it, even from the chain of the poster.
+        receive(
+            &mut contract,
+            poster_chain_id,
+            Some(attacker),
+            150,
+            reclaim(),
+        );
+        receive(
+            &mut contract,
+            attacker_chain_id,
+            Some(poster),
+            150,
+            reclaim(),
+        );
+        assert!(has_bounty(&contract, puzzle_id));
+        assert!(contract.runtime.outgoing_transfers().is_empty());
+
+        receive(&mut contract, poster_chain_id, Some(poster), 150, reclaim());
+        assert!(!has_bounty(&contract, puzzle_id));
+        let poster_account = Account {
+            chain_id: poster_chain_id,
+            owner: poster,
+        };
+        assert_eq!(
+            contract.runtime.outgoing_transfers().get(&poster_account),
+            Some(&amount)
+        );
+    }
+
+    #[test]
+    fn duplicate_and_expired_bounties_are_refunded() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let other_puzzle_id = DataBlobHash(CryptoHash::test_hash("other block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let poster = AccountOwner::from(CryptoHash::test_hash("poster"));
+        let poster_chain_id = ChainId(CryptoHash::test_hash("poster chain"));
+        let duplicate_chain_id = ChainId(CryptoHash::test_hash("duplicate chain"));
+        let expired_chain_id = ChainId(CryptoHash::test_hash("expired chain"));
+        let application_owner = contract.application_owner();
+        contract
+            .runtime
+            .set_owner_balance(application_owner, Amount::from_tokens(10));
+        let posted = |puzzle_id, amount, expiry| Message::BountyPosted {
+            puzzle_id,
+            amount: Amount::from_tokens(amount),
+            expiry: Timestamp::from(expiry),
+            poster,
+        };
+
+        receive(
+            &mut contract,
+            poster_chain_id,
+            None,
+            0,
+            posted(puzzle_id, 5, 100),
+        );
+        receive(
+            &mut contract,
+            duplicate_chain_id,
+            None,
+            10,
+            posted(puzzle_id, 3, 200),
+        );
+        // The bounty expired while the message was in flight.
+        receive(
+            &mut contract,
+            expired_chain_id,
+            None,
+            50,
+            posted(other_puzzle_id, 2, 50),
+        );
+        assert!(has_bounty(&contract, puzzle_id));
+        assert!(!has_bounty(&contract, other_puzzle_id));
+        let bounty = contract
+            .state
+            .bounties
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert_eq!(bounty.amount, Amount::from_tokens(5));
+        assert_eq!(bounty.poster.chain_id, poster_chain_id);
+        let refund = |chain_id| Account {
+            chain_id,
+            owner: poster,
+        };
+        let transfers = contract.runtime.outgoing_transfers();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(
+            transfers.get(&refund(duplicate_chain_id)),
+            Some(&Amount::from_tokens(3))
+        );
+        assert_eq!(
+            transfers.get(&refund(expired_chain_id)),
+            Some(&Amount::from_tokens(2))
+        );
+    }
+
+    /// The chain of a player of duels, with the player authenticated.
+    fn player_contract(chain_id: ChainId, player: AccountOwner) -> GolChallengeContract {
+        let runtime = ContractRuntime::new()
+            .with_chain_id(chain_id)
+            .with_application_id(ApplicationId::new(CryptoHash::test_hash("app")).with_abi())
+            .with_authenticated_signer(player)
+            .with_system_time(Timestamp::from(0));
+        GolChallengeContract::load(runtime).blocking_wait()
+    }
+
+    /// Executes an operation authenticated by the given owner at the given time.
+    fn execute(
+        contract: &mut GolChallengeContract,
+        owner: AccountOwner,
+        now: u64,
+        operation: Operation,
+    ) {
+        contract.runtime.set_authenticated_signer(Some(owner));
+        contract.runtime.set_system_time(Timestamp::from(now));
+        contract.execute_operation(operation).blocking_wait();
+    }
+
+    /// The copy of a duel kept by a chain.
+    fn duel(contract: &GolChallengeContract, duel_id: DuelId) -> Duel {
+        contract
+            .state
+            .duels
+            .get(&duel_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap()
+    }
+
+    /// The wins, losses and draws of a player on a chain.
+    fn duel_record(contract: &GolChallengeContract, owner: AccountOwner) -> (u32, u32, u32) {
+        let record = contract
+            .state
+            .duel_records
+            .get(&owner)
+            .blocking_wait()
+            .unwrap()
+            .unwrap_or_default();
+        (record.wins, record.losses, record.draws)
+    }
+
+    /// Creates a duel with the given deadline on the chain of the challenger, and accepts
+    /// it on the chain of the opponent.
+    fn accepted_duel(
+        challenger_contract: &mut GolChallengeContract,
+        opponent_contract: &mut GolChallengeContract,
+        puzzle_id: DataBlobHash,
+        (challenger, opponent): (AccountOwner, AccountOwner),
+        deadline: u64,
+    ) -> DuelId {
+        let challenger_chain_id = challenger_contract.runtime.chain_id();
+        let opponent_chain_id = opponent_contract.runtime.chain_id();
+        expect_blob_read(challenger_contract, puzzle_id);
+        let operation = Operation::CreateDuel {
+            puzzle_id,
+            opponent_chain_id,
+            opponent,
+            deadline: Timestamp::from(deadline),
+            metric: None,
+        };
+        execute(challenger_contract, challenger, 0, operation);
+        let duel_id = DuelId {
+            chain_id: challenger_chain_id,
+            index: 0,
+        };
+        let message = Message::DuelChallenge {
+            duel: duel(challenger_contract, duel_id),
+        };
+        receive(opponent_contract, challenger_chain_id, None, 5, message);
+        execute(
+            opponent_contract,
+            opponent,
+            10,
+            Operation::AcceptDuel { duel_id },
+        );
+        assert_eq!(
+            duel(opponent_contract, duel_id).status,
+            DuelStatus::Accepted
+        );
+        let message = Message::DuelAccepted { duel_id };
+        receive(challenger_contract, opponent_chain_id, None, 15, message);
+        assert_eq!(
+            duel(challenger_contract, duel_id).status,
+            DuelStatus::Accepted
+        );
+        duel_id
+    }
+
+    #[test]
+    fn duels_are_resolved_on_both_chains() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let (_, board) = block_puzzle();
+        let challenger = AccountOwner::from(CryptoHash::test_hash("challenger"));
+        let opponent = AccountOwner::from(CryptoHash::test_hash("opponent"));
+        let challenger_chain_id = ChainId(CryptoHash::test_hash("challenger chain"));
+        let opponent_chain_id = ChainId(CryptoHash::test_hash("opponent chain"));
+        let mut challenger_contract = player_contract(challenger_chain_id, challenger);
+        let mut opponent_contract = player_contract(opponent_chain_id, opponent);
+        let duel_id = accepted_duel(
+            &mut challenger_contract,
+            &mut opponent_contract,
+            puzzle_id,
+            (challenger, opponent),
+            100,
+        );
+        let submit = || Operation::SubmitDuelSolution {
+            duel_id,
+            board: board.clone(),
+        };
+        let solution = || Message::DuelSolution {
+            duel_id,
+            board: board.clone(),
+        };
+
+        // The opponent solves the puzzle first, as seen by both chains.
+        expect_blob_read(&mut opponent_contract, puzzle_id);
+        execute(&mut opponent_contract, opponent, 20, submit());
+        expect_blob_read(&mut challenger_contract, puzzle_id);
+        receive(
+            &mut challenger_contract,
+            opponent_chain_id,
+            None,
+            25,
+            solution(),
+        );
+        expect_blob_read(&mut challenger_contract, puzzle_id);
+        execute(&mut challenger_contract, challenger, 30, submit());
+        expect_blob_read(&mut opponent_contract, puzzle_id);
+        receive(
+            &mut opponent_contract,
+            challenger_chain_id,
+            None,
+            35,
+            solution(),
+        );
+
+        for contract in [&challenger_contract, &opponent_contract] {
+            let duel = duel(contract, duel_id);
+            assert_eq!(duel.status, DuelStatus::Resolved);
+            assert_eq!(duel.outcome, Some(DuelOutcome::OpponentWon));
+            assert_eq!(duel_record(contract, challenger), (0, 1, 0));
+            assert_eq!(duel_record(contract, opponent), (1, 0, 0));
+        }
+        // The received solutions are timed by the receiving chain.
+        let challenger_solution = duel(&opponent_contract, duel_id)
+            .challenger_solution
+            .unwrap();
+        assert_eq!(challenger_solution.timestamp, Timestamp::from(35));
+    }
+
+    #[test]
+    fn late_duel_solutions_do_not_count() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let (_, board) = block_puzzle();
+        let challenger = AccountOwner::from(CryptoHash::test_hash("challenger"));
+        let opponent = AccountOwner::from(CryptoHash::test_hash("opponent"));
+        let challenger_chain_id = ChainId(CryptoHash::test_hash("challenger chain"));
+        let opponent_chain_id = ChainId(CryptoHash::test_hash("opponent chain"));
+        let mut challenger_contract = player_contract(challenger_chain_id, challenger);
+        let mut opponent_contract = player_contract(opponent_chain_id, opponent);
+        let duel_id = accepted_duel(
+            &mut challenger_contract,
+            &mut opponent_contract,
+            puzzle_id,
+            (challenger, opponent),
+            100,
+        );
+
+        expect_blob_read(&mut challenger_contract, puzzle_id);
+        let operation = Operation::SubmitDuelSolution {
+            duel_id,
+            board: board.clone(),
+        };
+        execute(&mut challenger_contract, challenger, 30, operation);
+        // The opponent claims to have solved the puzzle earlier, but the solution is
+        // received after the deadline.
+        let message = Message::DuelSolution { duel_id, board };
+        receive(
+            &mut challenger_contract,
+            opponent_chain_id,
+            None,
+            150,
+            message,
+        );
+        assert!(duel(&challenger_contract, duel_id)
+            .opponent_solution
+            .is_none());
+        execute(
+            &mut challenger_contract,
+            challenger,
+            150,
+            Operation::ResolveDuel { duel_id },
+        );
+        let duel = duel(&challenger_contract, duel_id);
+        assert_eq!(duel.outcome, Some(DuelOutcome::ChallengerWon));
+        assert_eq!(duel_record(&challenger_contract, challenger), (1, 0, 0));
+        assert_eq!(duel_record(&challenger_contract, opponent), (0, 1, 0));
+    }
+
+    #[test]
+    fn unaccepted_duels_expire() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let challenger = AccountOwner::from(CryptoHash::test_hash("challenger"));
+        let opponent = AccountOwner::from(CryptoHash::test_hash("opponent"));
+        let challenger_chain_id = ChainId(CryptoHash::test_hash("challenger chain"));
+        let mut contract = player_contract(challenger_chain_id, challenger);
+
+        expect_blob_read(&mut contract, puzzle_id);
+        let operation = Operation::CreateDuel {
+            puzzle_id,
+            opponent_chain_id: ChainId(CryptoHash::test_hash("opponent chain")),
+            opponent,
+            deadline: Timestamp::from(100),
+            metric: None,
+        };
+        execute(&mut contract, challenger, 0, operation);
+        let duel_id = DuelId {
+            chain_id: challenger_chain_id,
+            index: 0,
+        };
+        execute(
+            &mut contract,
+            challenger,
+            150,
+            Operation::ResolveDuel { duel_id },
+        );
+        assert_eq!(duel(&contract, duel_id).status, DuelStatus::Expired);
+        assert_eq!(duel_record(&contract, challenger), (0, 0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Duel deadline has not passed")]
+    fn duels_are_not_resolved_before_their_deadline() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let challenger = AccountOwner::from(CryptoHash::test_hash("challenger"));
+        let opponent = AccountOwner::from(CryptoHash::test_hash("opponent"));
+        let mut challenger_contract = player_contract(
+            ChainId(CryptoHash::test_hash("challenger chain")),
+            challenger,
+        );
+        let mut opponent_contract =
+            player_contract(ChainId(CryptoHash::test_hash("opponent chain")), opponent);
+        let duel_id = accepted_duel(
+            &mut challenger_contract,
+            &mut opponent_contract,
+            puzzle_id,
+            (challenger, opponent),
+            100,
+        );
+        execute(
+            &mut challenger_contract,
+            challenger,
+            50,
+            Operation::ResolveDuel { duel_id },
+        );
+    }
+
+    /// Sends a team request of an owner to a scoring chain.
+    fn team_request(
+        contract: &mut GolChallengeContract,
+        owner: AccountOwner,
+        name: &str,
+        request: TeamRequest,
+    ) {
+        let message = Message::TeamRequest {
+            name: name.to_string(),
+            request,
+        };
+        let chain_id = ChainId(CryptoHash::test_hash("member chain"));
+        receive(contract, chain_id, Some(owner), 0, message);
+    }
+
+    /// A team of a scoring chain.
+    fn team(contract: &GolChallengeContract, name: &str) -> Team {
+        contract
+            .state
+            .teams
+            .get(name)
+            .blocking_wait()
+            .unwrap()
+            .unwrap()
+    }
+
+    /// The scores of the team leaderboard, in order.
+    fn team_scores(contract: &GolChallengeContract) -> Vec<(String, u64)> {
+        contract
+            .state
+            .sorted_teams
+            .indices()
+            .blocking_wait()
+            .unwrap()
+            .into_iter()
+            .map(|key| (key.name.clone(), key.score()))
+            .collect()
+    }
+
+    #[test]
+    fn teams_are_joined_by_invitation() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let creator = AccountOwner::from(CryptoHash::test_hash("creator"));
+        let member = AccountOwner::from(CryptoHash::test_hash("member"));
+        let other_creator = AccountOwner::from(CryptoHash::test_hash("other creator"));
+        let policy = TeamPolicy::UnionOfPuzzles;
+
+        team_request(&mut contract, creator, "a", TeamRequest::Create { policy });
+        // Owners cannot join without an invitation, nor be invited by anyone but the
+        // creator.
+        team_request(&mut contract, member, "a", TeamRequest::Join);
+        let invite = |member| TeamRequest::Invite { member };
+        team_request(&mut contract, member, "a", invite(member));
+        assert!(team(&contract, "a").invitations.is_empty());
+        assert_eq!(team(&contract, "a").members, [creator]);
+
+        team_request(&mut contract, creator, "a", invite(member));
+        assert_eq!(team(&contract, "a").invitations, [member]);
+        team_request(&mut contract, member, "a", TeamRequest::Join);
+        let team_a = team(&contract, "a");
+        assert_eq!(team_a.members, [creator, member]);
+        assert!(team_a.invitations.is_empty());
+
+        // Members of a team cannot create or join another one.
+        team_request(&mut contract, member, "b", TeamRequest::Create { policy });
+        assert!(contract
+            .state
+            .teams
+            .get("b")
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+        team_request(
+            &mut contract,
+            other_creator,
+            "c",
+            TeamRequest::Create { policy },
+        );
+        team_request(&mut contract, other_creator, "c", invite(member));
+        team_request(&mut contract, member, "c", TeamRequest::Join);
+        assert_eq!(team(&contract, "c").members, [other_creator]);
+        assert_eq!(
+            contract
+                .state
+                .owner_teams
+                .get(&member)
+                .blocking_wait()
+                .unwrap(),
+            Some("a".to_string())
+        );
+    }
+
+    #[test]
+    fn team_scores_follow_their_policy() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let (_, board) = block_puzzle();
+        let [union_creator, union_member, sum_creator, sum_member] =
+            ["union creator", "union member", "sum creator", "sum member"]
+                .map(|name| AccountOwner::from(CryptoHash::test_hash(name)));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let solve = |contract: &mut GolChallengeContract, owner, now| {
+            let message = Message::SolutionReport {
+                puzzle_id,
+                board: board.clone(),
+                timestamp: Timestamp::from(now),
+                owner,
+            };
+            receive(contract, solver_chain_id, None, now, message);
+        };
+        let teams = [
+            (
+                "union",
+                TeamPolicy::UnionOfPuzzles,
+                union_creator,
+                union_member,
+            ),
+            ("sum", TeamPolicy::SumOfPoints, sum_creator, sum_member),
+        ];
+        for (name, policy, creator, member) in teams {
+            team_request(&mut contract, creator, name, TeamRequest::Create { policy });
+            team_request(&mut contract, creator, name, TeamRequest::Invite { member });
+        }
+        team_request(&mut contract, union_member, "union", TeamRequest::Join);
+
+        solve(&mut contract, union_creator, 10);
+        solve(&mut contract, union_member, 20);
+        solve(&mut contract, sum_creator, 30);
+        // Members are credited for the solutions they reported before joining.
+        solve(&mut contract, sum_member, 40);
+        team_request(&mut contract, sum_member, "sum", TeamRequest::Join);
+
+        // The same puzzle solved by two members counts once.
+        let union_team = team(&contract, "union");
+        assert_eq!(union_team.solved_puzzles, 1);
+        assert_eq!(
+            union_team.points,
+            points(&contract, union_creator) + points(&contract, union_member)
+        );
+        let sum_team = team(&contract, "sum");
+        let sum_points = points(&contract, sum_creator) + points(&contract, sum_member);
+        assert_eq!(sum_team.points, sum_points);
+        assert_eq!(sum_team.solved_puzzles, 1);
+        assert_eq!(
+            team_scores(&contract),
+            [("sum".to_string(), sum_points), ("union".to_string(), 1)]
+        );
+
+        // Changing the policy moves the team in the leaderboard.
+        let policy = TeamPolicy::UnionOfPuzzles;
+        team_request(
+            &mut contract,
+            sum_creator,
+            "sum",
+            TeamRequest::SetPolicy { policy },
+        );
+        assert_eq!(
+            team_scores(&contract),
+            [("sum".to_string(), 1), ("union".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn publication_points_are_set_by_the_scoring_chain() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let creator = AccountOwner::from(CryptoHash::test_hash("creator"));
+        let creator_chain_id = ChainId(CryptoHash::test_hash("creator chain"));
+        let published = |name: &str| Message::PuzzlePublished {
+            puzzle_id: DataBlobHash(CryptoHash::test_hash(name)),
+            solution_hash: CryptoHash::test_hash("solution"),
+            timestamp: Timestamp::from(0),
+        };
+
+        // No points are credited by default, whatever the difficulty of the puzzle.
+        receive(
+            &mut contract,
+            creator_chain_id,
+            Some(creator),
+            10,
+            published("block"),
+        );
+        assert!(contract
+            .state
+            .scores
+            .owner_scores
+            .get(&creator)
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+
+        // Puzzles are only credited once registered, so that creators cannot farm points.
+        contract.state.publication_points.set(5);
+        receive(
+            &mut contract,
+            creator_chain_id,
+            Some(creator),
+            20,
+            published("second"),
+        );
+        assert_eq!(points(&contract, creator), 0);
+        let second_puzzle_id = DataBlobHash(CryptoHash::test_hash("second"));
+        expect_blob_read(&mut contract, second_puzzle_id);
+        contract
+            .execute_operation(Operation::RegisterPuzzle {
+                puzzle_id: second_puzzle_id,
+            })
+            .blocking_wait();
+        let score = contract
+            .state
+            .scores
+            .owner_scores
+            .get(&creator)
+            .blocking_wait()
+            .unwrap()
+            .expect("Creator should be credited");
+        assert_eq!(score.points, 5);
+
+        // Puzzles registered before their publication is received are credited too.
+        let third_puzzle_id = DataBlobHash(CryptoHash::test_hash("third"));
+        expect_blob_read(&mut contract, third_puzzle_id);
+        contract
+            .execute_operation(Operation::RegisterPuzzle {
+                puzzle_id: third_puzzle_id,
+            })
+            .blocking_wait();
+        assert_eq!(points(&contract, creator), 5);
+        receive(
+            &mut contract,
+            creator_chain_id,
+            Some(creator),
+            30,
+            published("third"),
+        );
+        assert_eq!(points(&contract, creator), 10);
+
+        // Unauthenticated publications are ignored.
+        receive(
+            &mut contract,
+            creator_chain_id,
+            None,
+            40,
+            published("fourth"),
+        );
+        assert!(!contract
+            .state
+            .verified_puzzles
+            .contains_key(&DataBlobHash(CryptoHash::test_hash("fourth")))
+            .blocking_wait()
+            .unwrap());
+    }
+
+    #[test]
+    fn achievements_are_checked_on_every_accepted_solution() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let (_, board) = block_puzzle();
+        let report = |timestamp: u64| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner: solver,
+        };
+        receive(&mut contract, solver_chain_id, None, 10, report(10));
+
+        // Achievements created after a solution was credited are earned when it is
+        // reported again.
+        for (name, kind, count) in [
+            ("first", AchievementKind::FirstSolver, 0),
+            ("one", AchievementKind::SolvedCount, 1),
+            ("two", AchievementKind::SolvedCount, 2),
+        ] {
+            let achievement = Achievement {
+                name: name.to_string(),
+                description: String::new(),
+                rule: AchievementRule {
+                    kind,
+                    difficulty: Some(Difficulty::Easy),
+                    count,
+                },
+            };
+            contract
+                .execute_operation(Operation::CreateAchievement { achievement })
+                .blocking_wait();
+        }
+        receive(&mut contract, solver_chain_id, None, 20, report(20));
+        let mut earned = contract
+            .state
+            .earned_achievements
+            .try_load_entry(&solver)
+            .blocking_wait()
+            .unwrap()
+            .expect("Achievements should be earned")
+            .indices()
+            .blocking_wait()
+            .unwrap();
+        earned.sort();
+        assert_eq!(earned, ["first", "one"]);
+    }
+
+    #[test]
+    fn puzzle_rankings_are_declared_in_their_blob() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let ranked_puzzle_id = DataBlobHash(CryptoHash::test_hash("ranked block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let (puzzle, board) = block_puzzle();
+        let ranking = QualityRanking {
+            metric: QualityMetric::LiveCells,
+            par: Some(4),
+        };
+        let ranked_puzzle = RankedPuzzle {
+            ranking: ranking.clone(),
+            blob: bcs::to_bytes(&puzzle).unwrap(),
+        };
+        let bytes = ranked_puzzle.to_bytes().unwrap();
+        // The blob is read once to register the puzzle, then to verify the solution.
+        for _ in 0..2 {
+            contract
+                .runtime
+                .add_expected_read_data_blob_requests(ranked_puzzle_id, bytes.clone());
+        }
+        contract
+            .execute_operation(Operation::RegisterPuzzle {
+                puzzle_id: ranked_puzzle_id,
+            })
+            .blocking_wait();
+        assert_eq!(
+            contract
+                .state
+                .puzzle_rankings
+                .get(&ranked_puzzle_id)
+                .blocking_wait()
+                .unwrap(),
+            Some(ranking)
+        );
+
+        let message = Message::SolutionReport {
+            puzzle_id: ranked_puzzle_id,
+            board,
+            timestamp: Timestamp::from(10),
+            owner: solver,
+        };
+        receive(&mut contract, solver_chain_id, None, 10, message);
+        let metrics = contract
+            .state
+            .quality_boards
+            .try_load_entry(&ranked_puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .expect("The solution should be ranked")
+            .owner_metrics
+            .get(&solver)
+            .blocking_wait()
+            .unwrap()
+            .expect("The solution should be ranked");
+        assert_eq!(metrics.live_cells, 4);
+    }
+
+    #[test]
+    fn best_solutions_are_kept_for_every_criterion() {
+        let runtime = ContractRuntime::new()
+            .with_chain_id(ChainId(CryptoHash::test_hash("user chain")))
+            .with_application_id(ApplicationId::new(CryptoHash::test_hash("app")).with_abi());
+        let mut contract = GolChallengeContract::load(runtime).blocking_wait();
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let (_, board) = block_puzzle();
+        let record = |timestamp: u64, steps: u16, live_cells: u32| SolutionRecord {
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner,
+            steps,
+            live_cells,
+        };
+        contract
+            .update_best_solution(puzzle_id, record(10, 3, 5))
+            .blocking_wait();
+        contract
+            .update_best_solution(puzzle_id, record(20, 5, 4))
+            .blocking_wait();
+        contract
+            .execute_operation(Operation::SetBestSolutionCriterion {
+                criterion: SolutionCriterion::FewestCells,
+            })
+            .blocking_wait();
+
+        let best_solutions = contract
+            .state
+            .best_solutions
+            .try_load_entry(&owner)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        for (criterion, timestamp) in [
+            (SolutionCriterion::FewestCells, 20),
+            (SolutionCriterion::FewestSteps, 10),
+            (SolutionCriterion::Earliest, 10),
+        ] {
+            let best = best_solutions
+                .get(&(puzzle_id, criterion))
+                .blocking_wait()
+                .unwrap()
+                .unwrap();
+            assert_eq!(best.timestamp, Timestamp::from(timestamp));
+        }
+    }
+
+    #[test]
+    fn daily_puzzles_are_scheduled_in_order() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let other_puzzle_id = DataBlobHash(CryptoHash::test_hash("other"));
+        let mut contract = scoring_contract(puzzle_id);
+        contract
+            .state
+            .registered_puzzles
+            .insert(&other_puzzle_id)
+            .unwrap();
+        contract
+            .execute_operation(Operation::ScheduleDailyPuzzles {
+                puzzle_ids: vec![puzzle_id, other_puzzle_id],
+            })
+            .blocking_wait();
+        let daily_puzzles = contract
+            .state
+            .daily_puzzles
+            .index_values()
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(daily_puzzles, vec![(0, puzzle_id), (1, other_puzzle_id)]);
+        assert_eq!(*contract.state.daily_schedule_end.get(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Puzzle is already scheduled")]
+    fn daily_puzzles_are_not_scheduled_twice() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        contract
+            .execute_operation(Operation::ScheduleDailyPuzzles {
+                puzzle_ids: vec![puzzle_id],
+            })
+            .blocking_wait();
+        contract
+            .execute_operation(Operation::ScheduleDailyPuzzles {
+                puzzle_ids: vec![puzzle_id],
+            })
+            .blocking_wait();
+    }
+
+    #[test]
+    #[should_panic(expected = "Puzzle is already scheduled")]
+    fn daily_puzzles_are_not_scheduled_twice_in_a_batch() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        contract
+            .execute_operation(Operation::ScheduleDailyPuzzles {
+                puzzle_ids: vec![puzzle_id, puzzle_id],
+            })
+            .blocking_wait();
+    }
+
+    #[test]
+    fn reported_boards_are_verified_if_enabled() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let (_, board) = block_puzzle();
+        let invalid_board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let trusted = AccountOwner::from(CryptoHash::test_hash("trusted"));
+        let rejected = AccountOwner::from(CryptoHash::test_hash("rejected"));
+        let verified = AccountOwner::from(CryptoHash::test_hash("verified"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let report = |owner, board: &Board, timestamp| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner,
+        };
+
+        // By default, the scoring chain trusts the user chains and reads no blob.
+        let message = report(trusted, &invalid_board, 10);
+        receive(&mut contract, solver_chain_id, None, 10, message);
+        let operation = Operation::SetSolutionVerification { enabled: true };
+        contract.execute_operation(operation).blocking_wait();
+        expect_blob_read(&mut contract, puzzle_id);
+        let message = report(rejected, &invalid_board, 20);
+        receive(&mut contract, solver_chain_id, None, 20, message);
+        expect_blob_read(&mut contract, puzzle_id);
+        let message = report(verified, &board, 30);
+        receive(&mut contract, solver_chain_id, None, 30, message);
+
+        assert_eq!(
+            solve_events(&contract, puzzle_id),
+            [
+                (trusted, Timestamp::from(10), 1),
+                (verified, Timestamp::from(30), 2),
+            ]
+        );
+        assert_eq!(points(&contract, rejected), 0);
+        assert!(contract
+            .state
+            .reported_solutions
+            .try_load_entry(&rejected)
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn resubmitted_solutions_keep_their_rank() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let (_, board) = block_puzzle();
+        let first_solver = AccountOwner::from(CryptoHash::test_hash("first solver"));
+        let second_solver = AccountOwner::from(CryptoHash::test_hash("second solver"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let report = |owner, timestamp| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner,
+        };
+
+        receive(
+            &mut contract,
+            solver_chain_id,
+            None,
+            10,
+            report(first_solver, 10),
+        );
+        receive(
+            &mut contract,
+            solver_chain_id,
+            None,
+            20,
+            report(second_solver, 20),
+        );
+        let first_points = points(&contract, first_solver);
+        // The first solver reports the puzzle again, with a later timestamp.
+        receive(
+            &mut contract,
+            solver_chain_id,
+            None,
+            30,
+            report(first_solver, 30),
+        );
+
+        assert_eq!(
+            solve_events(&contract, puzzle_id),
+            [
+                (first_solver, Timestamp::from(10), 1),
+                (second_solver, Timestamp::from(20), 2),
+            ]
+        );
+        let credited_solution = contract
+            .state
+            .reported_solutions
+            .try_load_entry(&first_solver)
+            .blocking_wait()
+            .unwrap()
+            .unwrap()
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert_eq!(credited_solution.timestamp, Timestamp::from(10));
+        assert_eq!(credited_solution.rank, 1);
+        assert_eq!(points(&contract, first_solver), first_points);
+        let solver_count = contract
+            .state
+            .solver_counts
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(solver_count, Some(2));
+    }
+
+    /// The owners credited in a season.
+    fn season_solvers(contract: &GolChallengeContract, name: &str) -> Vec<AccountOwner> {
+        let Some(solutions) = contract
+            .state
+            .season_solutions
+            .try_load_entry(name)
+            .blocking_wait()
+            .unwrap()
+        else {
+            return Vec::new();
+        };
+        let mut owners = solutions
+            .indices()
+            .blocking_wait()
+            .unwrap()
+            .into_iter()
+            .map(|(owner, _)| owner)
+            .collect::<Vec<_>>();
+        owners.sort();
+        owners
+    }
+
+    #[test]
+    fn solutions_are_attributed_to_seasons_by_their_timestamp() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let mut contract = scoring_contract(puzzle_id);
+        let (_, board) = block_puzzle();
+        let early_solver = AccountOwner::from(CryptoHash::test_hash("early solver"));
+        let late_solver = AccountOwner::from(CryptoHash::test_hash("late solver"));
+        let boundary_solver = AccountOwner::from(CryptoHash::test_hash("boundary solver"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        for (name, start, end) in [("spring", 0, 100), ("summer", 100, 200)] {
+            let operation = Operation::CreateSeason {
+                name: name.to_string(),
+                start: Timestamp::from(start),
+                end: Timestamp::from(end),
+                puzzle_ids: vec![puzzle_id],
+            };
+            contract.execute_operation(operation).blocking_wait();
+        }
+        let report = |owner, timestamp| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner,
+        };
+
+        // A solution from the spring is received in the summer.
+        let message = report(early_solver, 50);
+        receive(&mut contract, solver_chain_id, None, 150, message);
+        let message = report(boundary_solver, 100);
+        receive(&mut contract, solver_chain_id, None, 150, message);
+        let message = report(late_solver, 250);
+        receive(&mut contract, solver_chain_id, None, 250, message);
+
+        assert_eq!(season_solvers(&contract, "spring"), [early_solver]);
+        assert_eq!(season_solvers(&contract, "summer"), [boundary_solver]);
+        let season_points = |name: &str, owner| {
+            contract
+                .state
+                .season_scores
+                .try_load_entry(name)
+                .blocking_wait()
+                .unwrap()
+                .unwrap()
+                .owner_scores
+                .get(&owner)
+                .blocking_wait()
+                .unwrap()
+                .map(|score| score.points)
+        };
+        assert!(season_points("spring", early_solver).is_some());
+        assert_eq!(season_points("summer", early_solver), None);
+        assert_eq!(season_points("spring", boundary_solver), None);
+        // Solutions outside of every season still count on the main leaderboard.
+        assert!(points(&contract, late_solver) > 0);
+    }
+
+    #[test]
+    fn solves_and_registrations_are_emitted() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let other_puzzle_id = DataBlobHash(CryptoHash::test_hash("other block"));
+        let (_, board) = block_puzzle();
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let scoring_chain_id = ChainId(CryptoHash::test_hash("scoring chain"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let solutions_stream = || StreamName(SOLUTIONS_STREAM_NAME.to_vec());
+
+        // The user chain announces the verified solution.
+        let mut user_contract = player_contract(solver_chain_id, solver);
+        expect_blob_read(&mut user_contract, puzzle_id);
+        let operation = Operation::SubmitSolution {
+            puzzle_id,
+            board: board.clone(),
+            owner: None,
+            consent: None,
+            scoring_chain_ids: vec![scoring_chain_id],
+        };
+        execute(&mut user_contract, solver, 10, operation);
+        let event = user_contract
+            .runtime
+            .read_event(solver_chain_id, solutions_stream(), 0);
+        assert!(matches!(
+            event,
+            GolEvent::SolutionSubmitted { puzzle_id: id, owner, timestamp }
+                if id == puzzle_id && owner == solver && timestamp == Timestamp::from(10)
+        ));
+
+        // The scoring chain announces registrations and credited solutions.
+        let mut contract = scoring_contract(puzzle_id);
+        expect_blob_read(&mut contract, other_puzzle_id);
+        let operation = Operation::RegisterPuzzle {
+            puzzle_id: other_puzzle_id,
+        };
+        contract.execute_operation(operation).blocking_wait();
+        let event = contract.runtime.read_event(
+            scoring_chain_id,
+            StreamName(PUZZLES_STREAM_NAME.to_vec()),
+            0,
+        );
+        assert!(matches!(
+            event,
+            GolEvent::PuzzleRegistered { puzzle_id: id } if id == other_puzzle_id
+        ));
+        let message = Message::SolutionReport {
+            puzzle_id,
+            board,
+            timestamp: Timestamp::from(10),
+            owner: solver,
+        };
+        receive(&mut contract, solver_chain_id, None, 20, message);
+        let event = contract
+            .runtime
+            .read_event(scoring_chain_id, solutions_stream(), 0);
+        assert!(matches!(
+            event,
+            GolEvent::SolutionScored { puzzle_id: id, owner, rank: 1, points, .. }
+                if id == puzzle_id && owner == solver && points == 12
+        ));
+    }
+
+    /// The outcomes sent back by a scoring chain, with their destinations.
+    fn sent_outcomes(contract: &GolChallengeContract) -> Vec<(ChainId, ScoringOutcome)> {
+        contract
+            .runtime
+            .created_send_message_requests()
+            .iter()
+            .filter_map(|request| match &request.message {
+                Message::SolutionOutcome { outcome, .. } => Some((request.destination, *outcome)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn outcomes_are_sent_back_to_the_user_chain() {
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let unregistered_puzzle_id = DataBlobHash(CryptoHash::test_hash("unregistered"));
+        let mut contract = scoring_contract(puzzle_id);
+        let (_, board) = block_puzzle();
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let solver_chain_id = ChainId(CryptoHash::test_hash("solver chain"));
+        let other_chain_id = ChainId(CryptoHash::test_hash("other chain"));
+        let report = |puzzle_id, timestamp| Message::SolutionReport {
+            puzzle_id,
+            board: board.clone(),
+            timestamp: Timestamp::from(timestamp),
+            owner: solver,
+        };
+
+        receive(
+            &mut contract,
+            solver_chain_id,
+            None,
+            10,
+            report(puzzle_id, 10),
+        );
+        let message = report(puzzle_id, 20);
+        receive(&mut contract, other_chain_id, None, 20, message);
+        let message = report(unregistered_puzzle_id, 30);
+        receive(&mut contract, solver_chain_id, None, 30, message);
+        assert_eq!(
+            sent_outcomes(&contract),
+            [
+                (solver_chain_id, ScoringOutcome::Credited),
+                (other_chain_id, ScoringOutcome::Duplicate),
+                (solver_chain_id, ScoringOutcome::Ignored),
+            ]
+        );
+    }
+
+    /// A user chain with a local solution of the given owner, and a relayer authenticated
+    /// instead of the owner.
+    fn user_contract(
+        puzzle_id: DataBlobHash,
+        board: &Board,
+        owner: AccountOwner,
+        relayer: AccountOwner,
+    ) -> GolChallengeContract {
+        let runtime = ContractRuntime::new()
+            .with_chain_id(ChainId(CryptoHash::test_hash("user chain")))
+            .with_application_id(ApplicationId::new(CryptoHash::test_hash("app")).with_abi())
+            .with_authenticated_signer(relayer)
+            .with_system_time(Timestamp::from(0));
+        let mut contract = GolChallengeContract::load(runtime).blocking_wait();
+        let solution = Solution {
+            board: board.clone(),
+            timestamp: Timestamp::from(0),
+            owner,
+            scoring_statuses: Vec::new(),
+        };
+        contract
+            .state
+            .solutions
+            .insert(&puzzle_id, solution)
+            .unwrap();
+        contract
+    }
+
+    /// The consent of the owner of `secret_key` to report the board on the given chain.
+    fn sign_consent(
+        secret_key: &AccountSecretKey,
+        puzzle_id: DataBlobHash,
+        board: &Board,
+        scoring_chain_ids: &[ChainId],
+        chain_id: ChainId,
+        nonce: u64,
+    ) -> OwnerConsent {
+        let message = SubmissionConsent {
+            puzzle_id,
+            board_hash: board_hash(board),
+            scoring_chain_ids: scoring_chain_ids.to_vec(),
+            chain_id,
+            application_id: ApplicationId::new(CryptoHash::test_hash("app")),
+            nonce,
+        };
+        OwnerConsent {
+            nonce,
+            signature: secret_key.sign(&message),
+        }
+    }
+
+    /// Reports the local solution of the puzzle to the given scoring chains.
+    fn report(
+        contract: &mut GolChallengeContract,
+        puzzle_id: DataBlobHash,
+        scoring_chain_ids: &[ChainId],
+        consent: Option<OwnerConsent>,
+    ) {
+        let operation = Operation::ReportSolution {
+            puzzle_id,
+            scoring_chain_ids: scoring_chain_ids.to_vec(),
+            consent,
+        };
+        contract.execute_operation(operation).blocking_wait();
+    }
+
+    #[test]
+    fn report_solution_with_consent() {
+        let secret_key = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+        let owner = AccountOwner::from(secret_key.public());
+        let relayer = AccountOwner::from(CryptoHash::test_hash("relayer"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let scoring_chain_ids = [ChainId(CryptoHash::test_hash("scoring chain"))];
+        let mut contract = user_contract(puzzle_id, &board, owner, relayer);
+        let chain_id = contract.runtime.chain_id();
+
+        let consent = sign_consent(
+            &secret_key,
+            puzzle_id,
+            &board,
+            &scoring_chain_ids,
+            chain_id,
+            7,
+        );
+        report(&mut contract, puzzle_id, &scoring_chain_ids, Some(consent));
+        let solution = contract
+            .state
+            .solutions
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert_eq!(solution.scoring_statuses.len(), 1);
+        assert!(contract
+            .state
+            .used_consents
+            .contains(&(owner, 7))
+            .blocking_wait()
+            .unwrap());
+
+        // The owner does not need to consent to their own reports.
+        contract.runtime.set_authenticated_signer(Some(owner));
+        report(&mut contract, puzzle_id, &scoring_chain_ids, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Crediting another owner requires their consent")]
+    fn report_solution_without_consent() {
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let relayer = AccountOwner::from(CryptoHash::test_hash("relayer"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let scoring_chain_ids = [ChainId(CryptoHash::test_hash("scoring chain"))];
+        let mut contract = user_contract(puzzle_id, &board, owner, relayer);
+        report(&mut contract, puzzle_id, &scoring_chain_ids, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Consent was already used")]
+    fn reused_consent_is_rejected() {
+        let secret_key = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+        let owner = AccountOwner::from(secret_key.public());
+        let relayer = AccountOwner::from(CryptoHash::test_hash("relayer"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let scoring_chain_ids = [ChainId(CryptoHash::test_hash("scoring chain"))];
+        let mut contract = user_contract(puzzle_id, &board, owner, relayer);
+        let chain_id = contract.runtime.chain_id();
+
+        let consent = sign_consent(
+            &secret_key,
+            puzzle_id,
+            &board,
+            &scoring_chain_ids,
+            chain_id,
+            7,
+        );
+        report(
+            &mut contract,
+            puzzle_id,
+            &scoring_chain_ids,
+            Some(consent.clone()),
+        );
+        report(&mut contract, puzzle_id, &scoring_chain_ids, Some(consent));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid consent signature")]
+    fn consent_for_another_chain_is_rejected() {
+        let secret_key = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+        let owner = AccountOwner::from(secret_key.public());
+        let relayer = AccountOwner::from(CryptoHash::test_hash("relayer"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let scoring_chain_ids = [ChainId(CryptoHash::test_hash("scoring chain"))];
+        let mut contract = user_contract(puzzle_id, &board, owner, relayer);
+
+        // The consent was given for a submission from another user chain.
+        let other_chain_id = ChainId(CryptoHash::test_hash("other chain"));
+        let consent = sign_consent(
+            &secret_key,
+            puzzle_id,
+            &board,
+            &scoring_chain_ids,
+            other_chain_id,
+            7,
+        );
+        report(&mut contract, puzzle_id, &scoring_chain_ids, Some(consent));
+    }
+
+    #[test]
+    #[should_panic(expected = "Consent must be signed by the credited owner")]
+    fn consent_of_another_owner_is_rejected() {
+        let secret_key = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let relayer = AccountOwner::from(CryptoHash::test_hash("relayer"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let scoring_chain_ids = [ChainId(CryptoHash::test_hash("scoring chain"))];
+        let mut contract = user_contract(puzzle_id, &board, owner, relayer);
+        let chain_id = contract.runtime.chain_id();
+
+        let consent = sign_consent(
+            &secret_key,
+            puzzle_id,
+            &board,
+            &scoring_chain_ids,
+            chain_id,
+            7,
+        );
+        report(&mut contract, puzzle_id, &scoring_chain_ids, Some(consent));
+    }
+
+    #[test]
+    fn outcomes_are_recorded_for_each_scoring_chain() {
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+        let scoring_chain_ids = [
+            ChainId(CryptoHash::test_hash("scoring chain")),
+            ChainId(CryptoHash::test_hash("other scoring chain")),
+        ];
+        let mut contract = user_contract(puzzle_id, &board, owner, owner);
+        report(&mut contract, puzzle_id, &scoring_chain_ids, None);
+
+        let outcome = |timestamp, outcome| Message::SolutionOutcome {
+            puzzle_id,
+            timestamp: Timestamp::from(timestamp),
+            outcome,
+        };
+        let message = outcome(0, ScoringOutcome::Credited);
+        receive(&mut contract, scoring_chain_ids[0], None, 10, message);
+        // Outcomes of another submission of the puzzle are ignored.
+        let message = outcome(5, ScoringOutcome::Duplicate);
+        receive(&mut contract, scoring_chain_ids[1], None, 10, message);
+
+        let solution = contract
+            .state
+            .solutions
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        let statuses = solution
+            .scoring_statuses
+            .iter()
+            .map(|status| (status.chain_id, status.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                (scoring_chain_ids[0], Some(ScoringOutcome::Credited)),
+                (scoring_chain_ids[1], None),
+            ]
+        );
+    }
+
+    #[test]
+    fn reported_solutions_are_not_simulated_again() {
+        let solver = AccountOwner::from(CryptoHash::test_hash("solver"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let (_, board) = block_puzzle();
+        let scoring_chain_id = ChainId(CryptoHash::test_hash("scoring chain"));
+        let other_scoring_chain_id = ChainId(CryptoHash::test_hash("other scoring chain"));
+        let mut contract = player_contract(ChainId(CryptoHash::test_hash("user chain")), solver);
+
+        expect_blob_read(&mut contract, puzzle_id);
+        let operation = Operation::SubmitSolution {
+            puzzle_id,
+            board: board.clone(),
+            owner: None,
+            consent: None,
+            scoring_chain_ids: vec![scoring_chain_id],
+        };
+        execute(&mut contract, solver, 10, operation);
+        // No blob read is expected, so the solution is not verified again.
+        contract.runtime.set_system_time(Timestamp::from(20));
+        report(
+            &mut contract,
+            puzzle_id,
+            &[other_scoring_chain_id, scoring_chain_id],
+            None,
+        );
+
+        let reports = contract
+            .runtime
+            .created_send_message_requests()
+            .iter()
+            .filter_map(|request| match &request.message {
+                Message::SolutionReport {
+                    board: reported_board,
+                    timestamp,
+                    ..
+                } => {
+                    assert_eq!(board_hash(reported_board), board_hash(&board));
+                    Some((request.destination, *timestamp))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reports,
+            [
+                (scoring_chain_id, Timestamp::from(10)),
+                (other_scoring_chain_id, Timestamp::from(10)),
+                (scoring_chain_id, Timestamp::from(10)),
+            ]
+        );
+        let solution = contract
+            .state
+            .solutions
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        let chain_ids = solution
+            .scoring_statuses
+            .iter()
+            .map(|status| status.chain_id)
+            .collect::<Vec<_>>();
+        assert_eq!(chain_ids, [other_scoring_chain_id, scoring_chain_id]);
+        let history_len = contract
+            .state
+            .solution_history
+            .try_load_entry(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap()
+            .count();
+        assert_eq!(history_len, 1);
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::linera_base_types::DataBlobHash;
+use serde::{Deserialize, Serialize};
+
+/// How scoring chains award streak points for solving the puzzle of the day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "DailyConfigInput")]
+pub struct DailyConfig {
+    /// Points for each consecutive day on which the owner solved the puzzle of the day.
+    pub streak_points: u64,
+    /// The number of consecutive days after which the streak points stop increasing.
+    pub max_streak_days: u32,
+}
+
+impl Default for DailyConfig {
+    fn default() -> Self {
+        DailyConfig {
+            streak_points: 1,
+            max_streak_days: 7,
+        }
+    }
+}
+
+impl DailyConfig {
+    /// The points for solving the puzzle of the day with the given streak.
+    pub fn points(&self, streak: u32) -> u64 {
+        self.streak_points
+            .saturating_mul(u64::from(streak.min(self.max_streak_days)))
+    }
+}
+
+/// The consecutive days on which an owner solved the puzzle of the day.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct DailyStreak {
+    /// The number of consecutive days up to the last one.
+    pub current: u32,
+    /// The longest streak so far.
+    pub longest: u32,
+    /// The last day on which the owner solved the puzzle of the day. See `day_index`.
+    pub last_day: u64,
+}
+
+impl DailyStreak {
+    /// Records the puzzle of the given day as solved, and returns the current streak.
+    /// Returns 0 for a day before the last one, which no longer counts for the streak.
+    pub fn record(&mut self, day: u64) -> u32 {
+        if day < self.last_day {
+            return 0;
+        }
+        if self.current == 0 || day > self.last_day + 1 {
+            self.current = 1;
+        } else if day == self.last_day + 1 {
+            self.current += 1;
+        }
+        self.last_day = day;
+        self.longest = self.longest.max(self.current);
+        self.current
+    }
+}
+
+/// A puzzle of the daily schedule.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct DailyPuzzle {
+    /// The date, in the format `YYYY-MM-DD` (UTC).
+    pub date: String,
+    /// The ID of the puzzle.
+    pub puzzle_id: DataBlobHash,
+}
+
+/// Parses a date in the format `YYYY-MM-DD` (UTC), no earlier than 1970-01-01, and
+/// returns its day index. See `day_index`.
+pub fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?.parse::<u64>().ok()?;
+    let month = parts.next()?.parse::<u64>().ok()?;
+    let day = parts.next()?.parse::<u64>().ok()?;
+    if year < 1970 || !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+    // See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let index = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+    // Reject days beyond the end of the month.
+    (format_date(index) == date).then_some(index)
+}
+
+/// Formats a day index as a date in the format `YYYY-MM-DD` (UTC).
+pub fn format_date(index: u64) -> String {
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = index + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2000-03-01"), Some(11_017));
+        assert_eq!(parse_date("2024-02-29"), Some(19_782));
+        assert_eq!(format_date(19_782), "2024-02-29");
+        assert_eq!(format_date(20_000), "2024-10-04");
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("1969-12-31"), None);
+        assert_eq!(parse_date("2024-1-01"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_streak() {
+        let config = DailyConfig::default();
+        let mut streak = DailyStreak::default();
+        assert_eq!(streak.record(10), 1);
+        assert_eq!(streak.record(11), 2);
+        assert_eq!(streak.record(12), 3);
+        assert_eq!(config.points(3), 3);
+        assert_eq!(streak.record(14), 1);
+        assert_eq!(streak.longest, 3);
+        // A late solution of an earlier day awards nothing and keeps the streak.
+        assert_eq!(streak.record(13), 0);
+        assert_eq!(config.points(0), 0);
+        assert_eq!(streak.record(15), 2);
+        assert_eq!(streak.last_day, 15);
+        assert_eq!(config.points(10), 7);
+    }
+}
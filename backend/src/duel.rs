@@ -0,0 +1,128 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{Enum, InputObject, SimpleObject};
+use linera_sdk::linera_base_types::{ChainId, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::quality::{QualityMetric, QualityMetrics};
+
+/// The unique ID of a duel.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    InputObject,
+    SimpleObject,
+)]
+#[graphql(input_name = "DuelIdInput")]
+pub struct DuelId {
+    /// The chain of the challenger.
+    pub chain_id: ChainId,
+    /// The index of the duel among those created by the chain of the challenger.
+    pub index: u32,
+}
+
+/// The result of a duel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum DuelOutcome {
+    /// The challenger won.
+    ChallengerWon,
+    /// The opponent won.
+    OpponentWon,
+    /// Neither player won.
+    Draw,
+}
+
+/// Decides a duel given the time and the metrics of the valid solutions submitted by
+/// each player before the deadline. Without a metric, the earliest solution wins.
+/// Otherwise, the best solution according to the metric wins, and ties are broken by
+/// the earliest solution.
+pub fn decide_duel(
+    metric: Option<QualityMetric>,
+    challenger_solution: Option<(Timestamp, &QualityMetrics)>,
+    opponent_solution: Option<(Timestamp, &QualityMetrics)>,
+) -> DuelOutcome {
+    match (challenger_solution, opponent_solution) {
+        (None, None) => DuelOutcome::Draw,
+        (Some(_), None) => DuelOutcome::ChallengerWon,
+        (None, Some(_)) => DuelOutcome::OpponentWon,
+        (Some((challenger_time, challenger_metrics)), Some((opponent_time, opponent_metrics))) => {
+            if let Some(metric) = metric {
+                let challenger_value = challenger_metrics.value(metric);
+                let opponent_value = opponent_metrics.value(metric);
+                if metric.is_better(challenger_value, opponent_value) {
+                    return DuelOutcome::ChallengerWon;
+                }
+                if metric.is_better(opponent_value, challenger_value) {
+                    return DuelOutcome::OpponentWon;
+                }
+            }
+            match challenger_time.cmp(&opponent_time) {
+                std::cmp::Ordering::Less => DuelOutcome::ChallengerWon,
+                std::cmp::Ordering::Greater => DuelOutcome::OpponentWon,
+                std::cmp::Ordering::Equal => DuelOutcome::Draw,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(live_cells: u32) -> QualityMetrics {
+        QualityMetrics {
+            live_cells,
+            bounding_box_area: 9,
+            steps: 1,
+            symmetries: 0,
+        }
+    }
+
+    #[test]
+    fn test_decide_duel() {
+        let early = Timestamp::from(1);
+        let late = Timestamp::from(2);
+        let (small, large) = (metrics(3), metrics(5));
+        assert_eq!(decide_duel(None, None, None), DuelOutcome::Draw);
+        assert_eq!(
+            decide_duel(None, None, Some((late, &large))),
+            DuelOutcome::OpponentWon
+        );
+        assert_eq!(
+            decide_duel(None, Some((late, &small)), Some((early, &large))),
+            DuelOutcome::OpponentWon
+        );
+        assert_eq!(
+            decide_duel(
+                Some(QualityMetric::LiveCells),
+                Some((late, &small)),
+                Some((early, &large))
+            ),
+            DuelOutcome::ChallengerWon
+        );
+        assert_eq!(
+            decide_duel(
+                Some(QualityMetric::BoundingBoxArea),
+                Some((late, &small)),
+                Some((early, &large))
+            ),
+            DuelOutcome::OpponentWon
+        );
+        assert_eq!(
+            decide_duel(
+                Some(QualityMetric::LiveCells),
+                Some((early, &small)),
+                Some((early, &small))
+            ),
+            DuelOutcome::Draw
+        );
+    }
+}
@@ -13,7 +13,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// A GoL puzzle.
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[derive(Debug, Clone, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "PuzzleInput")]
 pub struct Puzzle {
     /// A title for this puzzle.
     pub title: String,
@@ -127,6 +128,51 @@ pub enum InvalidSolution {
     },
 }
 
+/// The largest board size accepted for a registered puzzle.
+pub const MAX_PUZZLE_SIZE: u16 = 128;
+
+/// Error type for structurally invalid puzzles.
+#[derive(Debug, Error, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InvalidPuzzle {
+    /// The size of the puzzle is zero or too large.
+    #[error("Invalid puzzle size {size} (must be between 1 and {max_size})")]
+    InvalidSize {
+        /// The size of the puzzle.
+        size: u16,
+        /// The maximal size allowed.
+        max_size: u16,
+    },
+
+    /// The step range of the puzzle is invalid.
+    #[error("The step range of the puzzle is invalid: [{min_steps}, {max_steps}]")]
+    InvalidStepRange {
+        /// The minimum allowed steps.
+        min_steps: u16,
+        /// The maximum allowed steps.
+        max_steps: u16,
+    },
+
+    /// A condition refers to cells outside of the board.
+    #[error("Condition {condition_index} (initial: {is_initial}) is out of the board")]
+    ConditionOutOfBounds {
+        /// Whether the condition is an initial or a final condition.
+        is_initial: bool,
+        /// The index of the condition.
+        condition_index: usize,
+    },
+
+    /// A rectangle condition can never be satisfied.
+    #[error(
+        "Condition {condition_index} (initial: {is_initial}) has unsatisfiable live-cell bounds"
+    )]
+    InvalidLiveCountBounds {
+        /// Whether the condition is an initial or a final condition.
+        is_initial: bool,
+        /// The index of the condition.
+        condition_index: usize,
+    },
+}
+
 /// Specific reasons why a condition failed.
 #[derive(Debug, Error, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ConditionFailureReason {
@@ -200,6 +246,28 @@ pub struct Board {
     live_cells: BTreeSet<Position>,
 }
 
+/// The colour of a player in the two-player Immigration variant of GoL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum PlayerColor {
+    /// The player moving first.
+    First,
+    /// The player moving second.
+    Second,
+}
+
+/// The state of a board of the Immigration variant, where each live cell has the colour
+/// of a player. Surviving cells keep their colour and new cells take the majority colour
+/// of their three parents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ColoredBoard {
+    /// The width and height of the board, in cells.
+    size: u16,
+    /// The coordinates of the live cells of the first player.
+    first_player_cells: BTreeSet<Position>,
+    /// The coordinates of the live cells of the second player.
+    second_player_cells: BTreeSet<Position>,
+}
+
 /// The state of a GoL cell. Used for computations.
 #[derive(Default)]
 struct Cell {
@@ -711,6 +779,16 @@ impl Board {
         }
     }
 
+    /// Returns the number of live cells.
+    pub fn live_cell_count(&self) -> u32 {
+        self.live_cells.len() as u32
+    }
+
+    /// Returns the coordinates of the live cells.
+    pub fn live_positions(&self) -> &BTreeSet<Position> {
+        &self.live_cells
+    }
+
     /// Returns all neighbors of cell `(x, y)`.
     fn neighbors(&self, position: Position) -> impl Iterator<Item = (u16, u16)> {
         let Position { x, y } = position;
@@ -841,7 +919,126 @@ impl Board {
     }
 }
 
+impl ColoredBoard {
+    /// Creates a new empty board with the given size.
+    pub fn new(size: u16) -> Self {
+        ColoredBoard {
+            size,
+            first_player_cells: BTreeSet::new(),
+            second_player_cells: BTreeSet::new(),
+        }
+    }
+
+    /// Whether the given position is on the board.
+    pub fn is_in_bounds(&self, position: Position) -> bool {
+        position.x < self.size && position.y < self.size
+    }
+
+    /// Returns the colour of the cell at the given position, if it is live.
+    pub fn color_at(&self, position: Position) -> Option<PlayerColor> {
+        if self.first_player_cells.contains(&position) {
+            Some(PlayerColor::First)
+        } else if self.second_player_cells.contains(&position) {
+            Some(PlayerColor::Second)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of live cells of a player.
+    pub fn live_count(&self, color: PlayerColor) -> u32 {
+        self.cells(color).len() as u32
+    }
+
+    /// Makes the cell at the given position live with the colour of a player.
+    pub fn set_cell(&mut self, position: Position, color: PlayerColor) {
+        match color {
+            PlayerColor::First => self.second_player_cells.remove(&position),
+            PlayerColor::Second => self.first_player_cells.remove(&position),
+        };
+        self.cells_mut(color).insert(position);
+    }
+
+    fn cells(&self, color: PlayerColor) -> &BTreeSet<Position> {
+        match color {
+            PlayerColor::First => &self.first_player_cells,
+            PlayerColor::Second => &self.second_player_cells,
+        }
+    }
+
+    fn cells_mut(&mut self, color: PlayerColor) -> &mut BTreeSet<Position> {
+        match color {
+            PlayerColor::First => &mut self.first_player_cells,
+            PlayerColor::Second => &mut self.second_player_cells,
+        }
+    }
+
+    /// Returns the uncoloured board with the same live cells.
+    pub fn to_board(&self) -> Board {
+        Board {
+            size: self.size,
+            live_cells: self
+                .first_player_cells
+                .union(&self.second_player_cells)
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Apply the GoL rules with the Immigration colouring to advance the board by one
+    /// step.
+    pub fn advance_once(&self) -> Self {
+        let board = self.to_board();
+        let mut next = ColoredBoard::new(self.size);
+        for (position, cell) in board.analyze_neighbors() {
+            if !cell.should_be_live_next() {
+                continue;
+            }
+            let color = self.color_at(position).unwrap_or_else(|| {
+                // A birth has exactly three live parents, so one colour has the majority.
+                let first_parents = board
+                    .neighbors(position)
+                    .filter(|&(x, y)| self.first_player_cells.contains(&Position { x, y }))
+                    .count();
+                if first_parents >= 2 {
+                    PlayerColor::First
+                } else {
+                    PlayerColor::Second
+                }
+            });
+            next.cells_mut(color).insert(position);
+        }
+        next
+    }
+}
+
 impl Puzzle {
+    /// Check that the puzzle itself is well-formed: the size and the step range are
+    /// valid, and every condition fits in the board and can be satisfied.
+    pub fn validate(&self) -> Result<(), InvalidPuzzle> {
+        if self.size == 0 || self.size > MAX_PUZZLE_SIZE {
+            return Err(InvalidPuzzle::InvalidSize {
+                size: self.size,
+                max_size: MAX_PUZZLE_SIZE,
+            });
+        }
+        if self.minimal_steps > self.maximal_steps || (self.is_strict && self.minimal_steps == 0) {
+            return Err(InvalidPuzzle::InvalidStepRange {
+                min_steps: self.minimal_steps,
+                max_steps: self.maximal_steps,
+            });
+        }
+        for (is_initial, conditions) in [
+            (true, &self.initial_conditions),
+            (false, &self.final_conditions),
+        ] {
+            for (condition_index, condition) in conditions.iter().enumerate() {
+                condition.validate(self.size, is_initial, condition_index)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Check that the board satisfies the given puzzle.
     pub fn check_solution(&self, board: &Board) -> Result<u16, InvalidSolution> {
         if self.minimal_steps > self.maximal_steps {
@@ -1032,6 +1229,51 @@ impl Cell {
 }
 
 impl Condition {
+    /// Check that the condition fits in a board of the given size and can be satisfied.
+    fn validate(
+        &self,
+        size: u16,
+        is_initial: bool,
+        condition_index: usize,
+    ) -> Result<(), InvalidPuzzle> {
+        match self {
+            Self::TestPosition { position, .. } => {
+                if position.x >= size || position.y >= size {
+                    return Err(InvalidPuzzle::ConditionOutOfBounds {
+                        is_initial,
+                        condition_index,
+                    });
+                }
+            }
+            Self::TestRectangle {
+                x_range,
+                y_range,
+                min_live_count,
+                max_live_count,
+            } => {
+                if x_range.start > x_range.end
+                    || y_range.start > y_range.end
+                    || x_range.end > size
+                    || y_range.end > size
+                {
+                    return Err(InvalidPuzzle::ConditionOutOfBounds {
+                        is_initial,
+                        condition_index,
+                    });
+                }
+                let area =
+                    u32::from(x_range.end - x_range.start) * u32::from(y_range.end - y_range.start);
+                if min_live_count > max_live_count || *min_live_count > area {
+                    return Err(InvalidPuzzle::InvalidLiveCountBounds {
+                        is_initial,
+                        condition_index,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn check(&self, board: &DirectBoard) -> Result<(), ConditionFailureReason> {
         match self {
             Self::TestPosition { position, is_live } => {
@@ -1080,6 +1322,35 @@ impl Condition {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_immigration_rule() {
+        // A blinker made of two first-player cells and one second-player cell.
+        let mut board = ColoredBoard::new(5);
+        board.set_cell(Position { x: 1, y: 2 }, PlayerColor::First);
+        board.set_cell(Position { x: 2, y: 2 }, PlayerColor::First);
+        board.set_cell(Position { x: 3, y: 2 }, PlayerColor::Second);
+        let next = board.advance_once();
+        // The center survives with its colour and both births take the majority colour.
+        assert_eq!(
+            next.color_at(Position { x: 2, y: 2 }),
+            Some(PlayerColor::First)
+        );
+        assert_eq!(
+            next.color_at(Position { x: 2, y: 1 }),
+            Some(PlayerColor::First)
+        );
+        assert_eq!(
+            next.color_at(Position { x: 2, y: 3 }),
+            Some(PlayerColor::First)
+        );
+        assert_eq!(next.live_count(PlayerColor::First), 3);
+        assert_eq!(next.live_count(PlayerColor::Second), 0);
+        assert_eq!(
+            next.to_board().live_cells,
+            board.to_board().advance_once().live_cells
+        );
+    }
+
     #[test]
     fn test_board_creation() {
         let board = Board::new(10);
@@ -1321,6 +1592,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_puzzle() {
+        let mut puzzle = Puzzle {
+            title: "Test".to_string(),
+            summary: "Test puzzle".to_string(),
+            difficulty: Difficulty::Easy,
+            size: 5,
+            metadata: String::new(),
+            minimal_steps: 1,
+            maximal_steps: 5,
+            enforce_initial_conditions: true,
+            is_strict: true,
+            initial_conditions: vec![Condition::TestPosition {
+                position: Position { x: 4, y: 4 },
+                is_live: true,
+            }],
+            final_conditions: vec![Condition::TestRectangle {
+                x_range: 0..5,
+                y_range: 3..5,
+                min_live_count: 2,
+                max_live_count: 10,
+            }],
+        };
+        assert_eq!(puzzle.validate(), Ok(()));
+
+        puzzle.size = 0;
+        assert_eq!(
+            puzzle.validate(),
+            Err(InvalidPuzzle::InvalidSize {
+                size: 0,
+                max_size: MAX_PUZZLE_SIZE
+            })
+        );
+
+        puzzle.size = 4;
+        assert_eq!(
+            puzzle.validate(),
+            Err(InvalidPuzzle::ConditionOutOfBounds {
+                is_initial: true,
+                condition_index: 0
+            })
+        );
+
+        puzzle.size = 5;
+        puzzle.minimal_steps = 0;
+        assert_eq!(
+            puzzle.validate(),
+            Err(InvalidPuzzle::InvalidStepRange {
+                min_steps: 0,
+                max_steps: 5
+            })
+        );
+
+        puzzle.minimal_steps = 1;
+        puzzle.final_conditions = vec![Condition::TestRectangle {
+            x_range: 0..2,
+            y_range: 0..2,
+            min_live_count: 5,
+            max_live_count: 10,
+        }];
+        assert_eq!(
+            puzzle.validate(),
+            Err(InvalidPuzzle::InvalidLiveCountBounds {
+                is_initial: false,
+                condition_index: 0
+            })
+        );
+    }
+
     #[test]
     fn test_check_puzzle_initial_conditions_fail() {
         let board = Board::new(5);
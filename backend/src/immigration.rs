@@ -0,0 +1,233 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::linera_base_types::ChainId;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::game::{ColoredBoard, PlayerColor, Position, MAX_PUZZLE_SIZE};
+
+/// The unique ID of an Immigration game.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    InputObject,
+    SimpleObject,
+)]
+#[graphql(input_name = "GameIdInput")]
+pub struct GameId {
+    /// The chain of the first player.
+    pub chain_id: ChainId,
+    /// The index of the game among those created by the chain of the first player.
+    pub index: u32,
+}
+
+/// The rules of an Immigration game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "ImmigrationRulesInput")]
+pub struct ImmigrationRules {
+    /// The width and height of the board, in cells.
+    pub size: u16,
+    /// The maximal number of cells placed by a player in one turn.
+    pub cells_per_turn: u32,
+    /// The number of turns of each player.
+    pub turns_per_player: u32,
+}
+
+/// An invalid move or set of rules.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InvalidMove {
+    /// The rules cannot be played.
+    #[error("Invalid rules: the board size is invalid or players cannot place cells")]
+    InvalidRules,
+    /// The game is over.
+    #[error("The game is over")]
+    GameOver,
+    /// The other player must play.
+    #[error("It is not the turn of this player")]
+    NotYourTurn,
+    /// Too many cells were placed.
+    #[error("Placed {count} cells but at most {max_count} are allowed")]
+    TooManyCells {
+        /// The number of cells placed.
+        count: usize,
+        /// The maximal number of cells per turn.
+        max_count: u32,
+    },
+    /// A cell is outside the board, live, or placed twice.
+    #[error("Cell ({x}, {y}) is outside the board or not empty")]
+    InvalidCell {
+        /// The first coordinate.
+        x: u16,
+        /// The second coordinate.
+        y: u16,
+    },
+}
+
+/// A two-player game of Life with the Immigration rule. Players alternately place cells
+/// of their colour on empty cells, then the board advances by one step. After the last
+/// turn, or once a player has no live cells left, the player with the most live cells
+/// wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ImmigrationGame {
+    /// The rules of the game.
+    pub rules: ImmigrationRules,
+    /// The current board.
+    pub board: ColoredBoard,
+    /// The number of turns played by both players.
+    pub turns_played: u32,
+    /// Whether the game is over.
+    pub is_over: bool,
+    /// The winner, once the game is over, unless it is a draw.
+    pub winner: Option<PlayerColor>,
+}
+
+impl ImmigrationGame {
+    /// Creates a new game with the given rules.
+    pub fn new(rules: ImmigrationRules) -> Result<Self, InvalidMove> {
+        if rules.size == 0
+            || rules.size > MAX_PUZZLE_SIZE
+            || rules.cells_per_turn == 0
+            || rules.turns_per_player == 0
+        {
+            return Err(InvalidMove::InvalidRules);
+        }
+        Ok(ImmigrationGame {
+            board: ColoredBoard::new(rules.size),
+            rules,
+            turns_played: 0,
+            is_over: false,
+            winner: None,
+        })
+    }
+
+    /// The player expected to play next.
+    pub fn next_player(&self) -> PlayerColor {
+        if self.turns_played % 2 == 0 {
+            PlayerColor::First
+        } else {
+            PlayerColor::Second
+        }
+    }
+
+    /// Plays the turn of a player: places the given cells, then advances the board.
+    pub fn play(&mut self, player: PlayerColor, cells: &[Position]) -> Result<(), InvalidMove> {
+        if self.is_over {
+            return Err(InvalidMove::GameOver);
+        }
+        if player != self.next_player() {
+            return Err(InvalidMove::NotYourTurn);
+        }
+        if cells.len() > self.rules.cells_per_turn as usize {
+            return Err(InvalidMove::TooManyCells {
+                count: cells.len(),
+                max_count: self.rules.cells_per_turn,
+            });
+        }
+        let mut placed = BTreeSet::new();
+        for &position in cells {
+            if !self.board.is_in_bounds(position)
+                || self.board.color_at(position).is_some()
+                || !placed.insert(position)
+            {
+                return Err(InvalidMove::InvalidCell {
+                    x: position.x,
+                    y: position.y,
+                });
+            }
+        }
+        for position in placed {
+            self.board.set_cell(position, player);
+        }
+        self.board = self.board.advance_once();
+        self.turns_played += 1;
+
+        let first_count = self.board.live_count(PlayerColor::First);
+        let second_count = self.board.live_count(PlayerColor::Second);
+        let is_extinct = self.turns_played >= 2 && (first_count == 0 || second_count == 0);
+        if is_extinct || self.turns_played >= 2 * self.rules.turns_per_player {
+            self.is_over = true;
+            self.winner = match first_count.cmp(&second_count) {
+                std::cmp::Ordering::Greater => Some(PlayerColor::First),
+                std::cmp::Ordering::Less => Some(PlayerColor::Second),
+                std::cmp::Ordering::Equal => None,
+            };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> ImmigrationRules {
+        ImmigrationRules {
+            size: 8,
+            cells_per_turn: 4,
+            turns_per_player: 2,
+        }
+    }
+
+    fn positions(cells: &[(u16, u16)]) -> Vec<Position> {
+        cells.iter().map(|&(x, y)| Position { x, y }).collect()
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let mut game = ImmigrationGame::new(rules()).unwrap();
+        assert_eq!(
+            game.play(PlayerColor::Second, &[]),
+            Err(InvalidMove::NotYourTurn)
+        );
+        assert_eq!(
+            game.play(
+                PlayerColor::First,
+                &positions(&[(0, 0), (0, 1), (0, 2), (0, 3), (0, 4)])
+            ),
+            Err(InvalidMove::TooManyCells {
+                count: 5,
+                max_count: 4
+            })
+        );
+        assert_eq!(
+            game.play(PlayerColor::First, &positions(&[(8, 0)])),
+            Err(InvalidMove::InvalidCell { x: 8, y: 0 })
+        );
+        assert_eq!(
+            game.play(PlayerColor::First, &positions(&[(1, 1), (1, 1)])),
+            Err(InvalidMove::InvalidCell { x: 1, y: 1 })
+        );
+        assert!(ImmigrationGame::new(ImmigrationRules { size: 0, ..rules() }).is_err());
+    }
+
+    #[test]
+    fn test_immigration_game() {
+        let mut game = ImmigrationGame::new(rules()).unwrap();
+        // A block of the first player is stable.
+        game.play(
+            PlayerColor::First,
+            &positions(&[(1, 1), (1, 2), (2, 1), (2, 2)]),
+        )
+        .unwrap();
+        // A single cell of the second player dies.
+        game.play(PlayerColor::Second, &positions(&[(6, 6)]))
+            .unwrap();
+        assert!(game.is_over);
+        assert_eq!(game.winner, Some(PlayerColor::First));
+        assert_eq!(
+            game.play(PlayerColor::First, &[]),
+            Err(InvalidMove::GameOver)
+        );
+    }
+}
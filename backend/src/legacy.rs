@@ -0,0 +1,40 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The layouts of the application state written by previous versions of the contract.
+
+use gol_challenge::game::Board;
+use linera_sdk::{
+    linera_base_types::{AccountOwner, DataBlobHash, Timestamp},
+    views::{
+        linera_views, CollectionView, MapView, RootView, SetView, ViewError, ViewStorageContext,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// The state of the first version of the application, before `schema_version` existed.
+#[derive(RootView)]
+#[view(context = ViewStorageContext)]
+pub struct LegacyStateV0 {
+    pub solutions: MapView<DataBlobHash, SolutionV0>,
+    pub registered_puzzles: SetView<DataBlobHash>,
+    pub reported_solutions: CollectionView<AccountOwner, MapView<DataBlobHash, Timestamp>>,
+}
+
+/// A solution of the first version, without scoring statuses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolutionV0 {
+    pub board: Board,
+    pub timestamp: Timestamp,
+    pub owner: AccountOwner,
+}
+
+impl LegacyStateV0 {
+    /// Whether the first version of the application never wrote to this state, e.g. on a
+    /// chain where the application never ran.
+    pub async fn is_empty(&self) -> Result<bool, ViewError> {
+        Ok(self.solutions.count().await? == 0
+            && self.registered_puzzles.count().await? == 0
+            && self.reported_solutions.count().await? == 0)
+    }
+}
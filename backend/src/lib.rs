@@ -5,17 +5,58 @@
 
 #![deny(missing_docs)]
 
+/// Achievement badges awarded by scoring chains.
+pub mod achievements;
+/// Campaigns of puzzles with prerequisites.
+pub mod campaign;
+/// The daily schedule of puzzles and the streaks of their solvers.
+pub mod daily;
+/// Head-to-head duels between two players.
+pub mod duel;
 /// Core library for the game engine and puzzles.
 pub mod game;
+/// The two-player Immigration game.
+pub mod immigration;
+/// Points awarded by scoring chains.
+pub mod points;
+/// Quality metrics of solutions, used to rank the solvers of a puzzle.
+pub mod quality;
+/// Rate limits of the messages received by scoring chains.
+pub mod rate_limit;
+/// Token rewards paid by scoring chains.
+pub mod rewards;
+/// Teams of owners and their leaderboard on scoring chains.
+pub mod teams;
+/// Puzzle templates, whose instances differ for each owner.
+pub mod templates;
+/// Community ratings and difficulty votes on puzzles.
+pub mod votes;
 
-use async_graphql::{Request, Response};
+use async_graphql::{Enum, InputObject, Request, Response};
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::{AccountOwner, ChainId, ContractAbi, DataBlobHash, ServiceAbi},
+    linera_base_types::{
+        AccountOwner, AccountSignature, Amount, ApplicationId, BcsHashable, BcsSignable, ChainId,
+        ContractAbi, CryptoHash, DataBlobHash, ServiceAbi, Timestamp,
+    },
 };
 use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Sha3_256};
 
-use crate::game::Board;
+use crate::{
+    achievements::Achievement,
+    campaign::CampaignPuzzle,
+    daily::DailyConfig,
+    duel::DuelId,
+    game::{Board, Position, Puzzle},
+    immigration::{GameId, ImmigrationRules},
+    points::PointsConfig,
+    quality::QualityMetric,
+    rate_limit::RateLimits,
+    rewards::PuzzleReward,
+    teams::TeamPolicy,
+    votes::PuzzleVote,
+};
 
 /// The ABI of the Game-of-Life challenge.
 pub struct GolChallengeAbi;
@@ -31,8 +72,173 @@ pub enum Operation {
         board: Board,
         /// Optional owner to credit instead of the current authenticated owner.
         owner: Option<AccountOwner>,
-        /// The scoring chain to use.
-        scoring_chain_id: Option<ChainId>,
+        /// The consent of the owner, required if the owner is not the authenticated owner.
+        consent: Option<OwnerConsent>,
+        /// The scoring chains to report the solution to.
+        scoring_chain_ids: Vec<ChainId>,
+    },
+    /// Send the commitment to a solution to scoring chains, without revealing the board.
+    /// See `solution_commitment`.
+    CommitSolution {
+        /// The ID of the puzzle in blob storage.
+        puzzle_id: DataBlobHash,
+        /// The commitment to the board, the owner and a secret salt.
+        commitment: String,
+        /// The owner credited for the solution, which must be the authenticated owner.
+        owner: Option<AccountOwner>,
+        /// The scoring chains to send the commitment to.
+        scoring_chain_ids: Vec<ChainId>,
+    },
+    /// Submit a solution locally for verification and reveal it to scoring chains
+    /// that previously received its commitment. Scoring chains credit the solution at the
+    /// time of the commitment, ranked after the owners credited before the commitment.
+    RevealSolution {
+        /// The ID of the puzzle in blob storage.
+        puzzle_id: DataBlobHash,
+        /// The board of the solution.
+        board: Board,
+        /// The salt used in the commitment.
+        salt: String,
+        /// Optional owner to credit instead of the current authenticated owner.
+        owner: Option<AccountOwner>,
+        /// The consent of the owner, required if the owner is not the authenticated owner.
+        consent: Option<OwnerConsent>,
+        /// The scoring chains to reveal the solution to.
+        scoring_chain_ids: Vec<ChainId>,
+    },
+    /// Report a solution previously submitted on this chain to more scoring chains,
+    /// without verifying it again.
+    ReportSolution {
+        /// The ID of the puzzle in blob storage.
+        puzzle_id: DataBlobHash,
+        /// The scoring chains to report the solution to.
+        scoring_chain_ids: Vec<ChainId>,
+        /// The consent of the owner of the solution, required if they are not the
+        /// authenticated owner.
+        consent: Option<OwnerConsent>,
+    },
+    /// Post a bounty for solving a puzzle: the amount is transferred from the
+    /// authenticated owner to the given scoring chain, which pays it to the first owner
+    /// reporting a valid solution before the expiry.
+    PostBounty {
+        /// The ID of the puzzle in blob storage. The puzzle does not need to be registered.
+        puzzle_id: DataBlobHash,
+        /// The amount of the bounty.
+        amount: Amount,
+        /// The time after which the bounty may be reclaimed instead.
+        expiry: Timestamp,
+        /// The scoring chain holding the bounty.
+        scoring_chain_id: ChainId,
+    },
+    /// Reclaim an expired bounty posted by the authenticated owner.
+    ReclaimBounty {
+        /// The ID of the puzzle of the bounty.
+        puzzle_id: DataBlobHash,
+        /// The scoring chain holding the bounty.
+        scoring_chain_id: ChainId,
+    },
+    /// Challenge an owner of another user chain to a duel on a puzzle.
+    CreateDuel {
+        /// The ID of the puzzle in blob storage.
+        puzzle_id: DataBlobHash,
+        /// The chain of the opponent.
+        opponent_chain_id: ChainId,
+        /// The opponent.
+        opponent: AccountOwner,
+        /// The time after which solutions are no longer accepted.
+        deadline: Timestamp,
+        /// The metric deciding the winner, if any. Otherwise, the first valid solution wins.
+        metric: Option<QualityMetric>,
+    },
+    /// Accept a duel as the opponent.
+    AcceptDuel {
+        /// The ID of the duel.
+        duel_id: DuelId,
+    },
+    /// Submit a solution to an accepted duel, as one of the players. Only the first
+    /// solution of each player counts.
+    SubmitDuelSolution {
+        /// The ID of the duel.
+        duel_id: DuelId,
+        /// The board of the solution.
+        board: Board,
+    },
+    /// Resolve a duel after its deadline, on the chain of either player. Duels are
+    /// resolved automatically once both players submitted a solution. Each chain times
+    /// the solution of the other player when it receives it, so solutions received after
+    /// the deadline do not count.
+    ResolveDuel {
+        /// The ID of the duel.
+        duel_id: DuelId,
+    },
+    /// Start a two-player Immigration game against an owner of another user chain. The
+    /// authenticated owner plays first.
+    CreateImmigrationGame {
+        /// The chain of the opponent.
+        opponent_chain_id: ChainId,
+        /// The opponent.
+        opponent: AccountOwner,
+        /// The rules of the game.
+        rules: ImmigrationRules,
+    },
+    /// Play a turn of an Immigration game: place cells of the colour of the
+    /// authenticated owner, then advance the board.
+    PlayImmigrationTurn {
+        /// The ID of the game.
+        game_id: GameId,
+        /// The empty cells to make live.
+        cells: Vec<Position>,
+    },
+    /// Set how the best solution of each owner and puzzle is chosen among the solutions
+    /// submitted on this chain.
+    SetBestSolutionCriterion {
+        /// The new criterion.
+        criterion: SolutionCriterion,
+    },
+    /// Rate a puzzle and vote on its difficulty, as the authenticated owner. Scoring
+    /// chains only count the first vote of owners credited for the puzzle.
+    RatePuzzle {
+        /// The ID of the puzzle in blob storage.
+        puzzle_id: DataBlobHash,
+        /// The rating and the perceived difficulty.
+        vote: PuzzleVote,
+        /// The scoring chains to send the vote to.
+        scoring_chain_ids: Vec<ChainId>,
+    },
+    /// Create a team on a scoring chain, with the authenticated owner as its first member.
+    /// An owner belongs to at most one team per scoring chain.
+    CreateTeam {
+        /// The unique name of the team.
+        name: String,
+        /// How the score of the team is computed.
+        policy: TeamPolicy,
+        /// The scoring chain of the team.
+        scoring_chain_id: ChainId,
+    },
+    /// Invite an owner to a team created by the authenticated owner.
+    InviteTeamMember {
+        /// The name of the team.
+        name: String,
+        /// The owner to invite.
+        member: AccountOwner,
+        /// The scoring chain of the team.
+        scoring_chain_id: ChainId,
+    },
+    /// Join a team as the authenticated owner, after being invited.
+    JoinTeam {
+        /// The name of the team.
+        name: String,
+        /// The scoring chain of the team.
+        scoring_chain_id: ChainId,
+    },
+    /// Change how the score of a team created by the authenticated owner is computed.
+    SetTeamPolicy {
+        /// The name of the team.
+        name: String,
+        /// The new policy.
+        policy: TeamPolicy,
+        /// The scoring chain of the team.
+        scoring_chain_id: ChainId,
     },
     // Scoring appchain only
     /// Register a puzzle to activate scoring for it.
@@ -40,6 +246,214 @@ pub enum Operation {
         /// The ID of the puzzle to register.
         puzzle_id: DataBlobHash,
     },
+    /// Set whether reported solutions are verified again by this scoring chain. This
+    /// costs more fuel but does not require trusting the user chains.
+    SetSolutionVerification {
+        /// Whether to verify reported solutions.
+        enabled: bool,
+    },
+    /// Set how points are awarded for new solutions on this scoring chain.
+    SetPointsConfig {
+        /// The new configuration.
+        config: PointsConfig,
+    },
+    /// Set the rate limits of the solutions, commitments and votes received by this
+    /// scoring chain. Messages beyond the limits are rejected.
+    SetRateLimits {
+        /// The new limits.
+        limits: RateLimits,
+    },
+    /// Set the points credited by this scoring chain to the creator of each published
+    /// puzzle, once registered. No points are credited by default.
+    SetPublicationPoints {
+        /// The new number of points.
+        points: u64,
+    },
+    /// Create a season: solutions to the given puzzles are credited to the season if
+    /// their timestamps are within the time window of the season.
+    CreateSeason {
+        /// The unique name of the season.
+        name: String,
+        /// The start of the season, included.
+        start: Timestamp,
+        /// The end of the season, excluded.
+        end: Timestamp,
+        /// The puzzles counting for the season.
+        puzzle_ids: Vec<DataBlobHash>,
+    },
+    /// Create a campaign: an ordered list of puzzles, each of them counting only after
+    /// its prerequisites were solved by the same owner. A puzzle belongs to at most one
+    /// campaign.
+    CreateCampaign {
+        /// The unique name of the campaign.
+        name: String,
+        /// The registered puzzles of the campaign. Prerequisites must come first.
+        puzzles: Vec<CampaignPuzzle>,
+    },
+    /// Create an achievement, earned by owners once one of their credited solutions meets
+    /// its rule.
+    CreateAchievement {
+        /// The new achievement, with a unique name.
+        achievement: Achievement,
+    },
+    /// Publish a puzzle with a reference solution proving that it is solvable. The puzzle is
+    /// stored in a new data blob and recorded as verified, together with the hash of the
+    /// reference solution, then the scoring chains record the authenticated owner as its
+    /// creator, crediting the publication points they set, if any, once they register the
+    /// puzzle. The reference solution is public, like every operation of a block.
+    PublishPuzzle {
+        /// The puzzle to publish.
+        puzzle: Puzzle,
+        /// A solution of the puzzle, also satisfying its initial conditions.
+        reference_solution: Board,
+        /// The scoring chains recording the creator.
+        scoring_chain_ids: Vec<ChainId>,
+    },
+    /// Queue registered puzzles into the daily schedule: each puzzle is the puzzle of the
+    /// day following the last scheduled one, or starting today.
+    ScheduleDailyPuzzles {
+        /// The registered puzzles to schedule, in order.
+        puzzle_ids: Vec<DataBlobHash>,
+    },
+    /// Set how streak points are awarded for solving the puzzle of the day.
+    SetDailyConfig {
+        /// The new configuration.
+        config: DailyConfig,
+    },
+    /// Transfer tokens from the balance of this scoring chain to its reward pool. Requires
+    /// an owner of the chain.
+    FundRewardPool {
+        /// The amount to transfer.
+        amount: Amount,
+    },
+    /// Set or remove the token reward of a registered puzzle.
+    SetPuzzleReward {
+        /// The ID of the registered puzzle.
+        puzzle_id: DataBlobHash,
+        /// The reward, if any.
+        reward: Option<PuzzleReward>,
+    },
+    /// Migrate the state written by a previous version of the application, so that the
+    /// service may query it again. Any block using the application migrates its state:
+    /// this operation only makes sure that a block does, right after an upgrade.
+    MigrateState,
+}
+
+/// How to choose the best of several solutions to the same puzzle. Ties are broken by
+/// the other criteria, in the order below.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, Enum)]
+pub enum SolutionCriterion {
+    /// The solution with the fewest live cells.
+    FewestCells,
+    /// The solution reaching the final conditions in the fewest steps.
+    FewestSteps,
+    /// The earliest solution.
+    #[default]
+    Earliest,
+}
+
+impl SolutionCriterion {
+    /// All the criteria.
+    pub const ALL: [SolutionCriterion; 3] = [
+        SolutionCriterion::FewestCells,
+        SolutionCriterion::FewestSteps,
+        SolutionCriterion::Earliest,
+    ];
+}
+
+/// The name of the event stream for registered puzzles.
+pub const PUZZLES_STREAM_NAME: &[u8] = b"puzzles";
+/// The name of the event stream for submitted and scored solutions.
+pub const SOLUTIONS_STREAM_NAME: &[u8] = b"solutions";
+
+/// Events emitted by the Game-of-Life challenge, so that other applications can react
+/// to solves and registrations.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum GolEvent {
+    /// A puzzle was registered on a scoring chain. Emitted on `PUZZLES_STREAM_NAME`.
+    PuzzleRegistered {
+        /// The ID of the puzzle.
+        puzzle_id: DataBlobHash,
+    },
+    /// A solution was verified and stored on a user chain. Emitted on
+    /// `SOLUTIONS_STREAM_NAME`.
+    SolutionSubmitted {
+        /// The ID of the puzzle that was solved.
+        puzzle_id: DataBlobHash,
+        /// The user credited for the solution.
+        owner: AccountOwner,
+        /// The timestamp of the solution.
+        timestamp: Timestamp,
+    },
+    /// A solution was credited on a scoring chain. Emitted on `SOLUTIONS_STREAM_NAME`.
+    SolutionScored {
+        /// The ID of the puzzle that was solved.
+        puzzle_id: DataBlobHash,
+        /// The user credited for the solution.
+        owner: AccountOwner,
+        /// The timestamp of the solution.
+        timestamp: Timestamp,
+        /// The rank of the owner among the solvers of the puzzle, starting at 1.
+        rank: u32,
+        /// The points awarded.
+        points: u64,
+    },
+}
+
+/// Computes the commitment to a solution, used to submit the solution in two phases:
+/// first the commitment, then the board and the salt. Returns the hex-encoded SHA3-256
+/// hash of the BCS serialization of `(board, owner, salt)`.
+pub fn solution_commitment(board: &Board, owner: &AccountOwner, salt: &str) -> String {
+    let bytes = bcs::to_bytes(&(board, owner, salt)).expect("Serialization should not fail");
+    Sha3_256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// How long a scoring chain accepts the reveal of a solution commitment after receiving
+/// it, in microseconds. Solutions revealed later are scored like reported solutions.
+pub const SOLUTION_COMMIT_TTL_MICROS: u64 = 3_600_000_000;
+
+/// The number of whole days (UTC) between the Unix epoch and the given time.
+pub fn day_index(timestamp: Timestamp) -> u64 {
+    timestamp.micros() / 86_400_000_000
+}
+
+/// What an owner signs to let another signer submit a solution on their behalf, e.g. a
+/// relayer sponsoring the submission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionConsent {
+    /// The ID of the puzzle.
+    pub puzzle_id: DataBlobHash,
+    /// The hash of the board of the solution. See `board_hash`.
+    pub board_hash: CryptoHash,
+    /// The scoring chains the solution is reported to.
+    pub scoring_chain_ids: Vec<ChainId>,
+    /// The user chain submitting the solution.
+    pub chain_id: ChainId,
+    /// The application receiving the submission.
+    pub application_id: ApplicationId,
+    /// A number used only once by the owner on each user chain.
+    pub nonce: u64,
+}
+
+impl BcsSignable<'_> for SubmissionConsent {}
+
+/// The signed consent of an owner to be credited for a solution.
+#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
+pub struct OwnerConsent {
+    /// The nonce of the signed `SubmissionConsent`.
+    pub nonce: u64,
+    /// The signature of the `SubmissionConsent` by the owner.
+    pub signature: AccountSignature,
+}
+
+impl BcsHashable<'_> for Board {}
+
+/// The hash of a board, used in `SubmissionConsent`.
+pub fn board_hash(board: &Board) -> CryptoHash {
+    CryptoHash::new(board)
 }
 
 impl ContractAbi for GolChallengeAbi {
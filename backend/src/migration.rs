@@ -0,0 +1,392 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Migrations of the application state written by previous versions of the contract.
+//!
+//! The fields of a `RootView` are stored under keys derived from their position, so
+//! inserting a field or changing the type of stored values requires a migration. Each
+//! migration reads the old layout through a view of the `legacy` module over the same
+//! storage, writes the data in the new layout, then bumps `schema_version`.
+//!
+//! Messages are not migrated: the messages of the first version cannot be decoded by
+//! later versions, so user chains should not have solution reports in flight when
+//! scoring chains are upgraded.
+//!
+//! Migrations never read blobs: reading a missing blob aborts the block, which would
+//! prevent the chain from ever loading its state.
+
+use std::collections::BTreeMap;
+
+use linera_sdk::views::{RootView, View, ViewError, ViewStorageContext};
+
+use crate::{
+    legacy::LegacyStateV0,
+    state::{CreditedSolution, GolChallengeState, Solution, SolveEvent, SCHEMA_VERSION},
+};
+
+/// Loads the state, migrating it first if it was written by a previous version.
+pub async fn load_and_migrate(context: ViewStorageContext) -> Result<GolChallengeState, ViewError> {
+    let state = GolChallengeState::load(context.clone()).await?;
+    let version = *state.schema_version.get();
+    assert!(
+        version <= SCHEMA_VERSION,
+        "The state was written by a newer version of the application"
+    );
+    if version == SCHEMA_VERSION {
+        return Ok(state);
+    }
+    if version < 1 {
+        log::info!("Migrating the state to version 1");
+        migrate_to_v1(context.clone()).await?;
+    }
+    GolChallengeState::load(context).await
+}
+
+/// Adds empty scoring statuses to the local solutions, and keeps the registered puzzles
+/// aside in `legacy_puzzles`. Their blobs were never validated, and may be missing, so they
+/// must be registered again.
+///
+/// The reported solutions are credited again, ranking the solvers of each puzzle by
+/// timestamp, with no points since the difficulty of the puzzles is unknown until their
+/// blobs are validated. Their points are credited when the puzzles are registered again.
+async fn migrate_to_v1(context: ViewStorageContext) -> Result<(), ViewError> {
+    let mut legacy = LegacyStateV0::load(context.clone()).await?;
+    let solutions = legacy.solutions.index_values().await?;
+    let registered_puzzles = legacy.registered_puzzles.indices().await?;
+    let mut puzzle_solvers = BTreeMap::<_, Vec<_>>::new();
+    for owner in legacy.reported_solutions.indices().await? {
+        let solved_puzzles = legacy
+            .reported_solutions
+            .try_load_entry(&owner)
+            .await?
+            .expect("Indices should have entries");
+        for (puzzle_id, timestamp) in solved_puzzles.index_values().await? {
+            puzzle_solvers
+                .entry(puzzle_id)
+                .or_default()
+                .push((timestamp, owner));
+        }
+    }
+    // The local solutions keep their field, but the other fields of the legacy state
+    // overlap with new ones.
+    legacy.registered_puzzles.clear();
+    legacy.reported_solutions.clear();
+    legacy.save().await?;
+
+    let mut state = GolChallengeState::load(context).await?;
+    for (puzzle_id, solution) in solutions {
+        let solution = Solution {
+            board: solution.board,
+            timestamp: solution.timestamp,
+            owner: solution.owner,
+            scoring_statuses: Vec::new(),
+        };
+        state.solutions.insert(&puzzle_id, solution)?;
+    }
+    for puzzle_id in registered_puzzles {
+        state.legacy_puzzles.insert(&puzzle_id)?;
+    }
+    for (puzzle_id, mut solvers) in puzzle_solvers {
+        solvers.sort();
+        let solve_log = state.solve_logs.load_entry_mut(&puzzle_id).await?;
+        for (index, (timestamp, owner)) in solvers.iter().enumerate() {
+            solve_log.push(SolveEvent {
+                owner: *owner,
+                timestamp: *timestamp,
+                rank: index as u32 + 1,
+            });
+        }
+        for (index, (timestamp, owner)) in solvers.iter().enumerate() {
+            let credited_solution = CreditedSolution {
+                timestamp: *timestamp,
+                rank: index as u32 + 1,
+                points: 0,
+            };
+            state
+                .reported_solutions
+                .load_entry_mut(owner)
+                .await?
+                .insert(&puzzle_id, credited_solution)?;
+            state.scores.credit(*owner, 0, true).await;
+        }
+        state
+            .solver_counts
+            .insert(&puzzle_id, solvers.len() as u32)?;
+    }
+    state.schema_version.set(1);
+    state.save().await
+}
+
+#[cfg(test)]
+mod tests {
+    use gol_challenge::{
+        game::{Board, Condition, Difficulty, Position, Puzzle},
+        points::PointsConfig,
+        Operation,
+    };
+    use linera_sdk::{
+        linera_base_types::{AccountOwner, CryptoHash, DataBlobHash, Timestamp},
+        util::BlockingWait,
+        Contract as _, ContractRuntime,
+    };
+
+    use super::*;
+    use crate::{legacy::SolutionV0, GolChallengeContract};
+
+    /// Writes the solutions reported to a scoring chain of the first version, as
+    /// `(owner, puzzle_id, timestamp)`.
+    fn report_legacy_solutions(
+        context: ViewStorageContext,
+        solutions: &[(AccountOwner, DataBlobHash, u64)],
+    ) {
+        let mut legacy = LegacyStateV0::load(context)
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        for (owner, puzzle_id, timestamp) in solutions {
+            legacy.registered_puzzles.insert(puzzle_id).unwrap();
+            legacy
+                .reported_solutions
+                .load_entry_mut(owner)
+                .blocking_wait()
+                .unwrap()
+                .insert(puzzle_id, Timestamp::from(*timestamp))
+                .unwrap();
+        }
+        legacy.save().blocking_wait().unwrap();
+    }
+
+    #[test]
+    fn migrate_from_v0() {
+        let runtime = ContractRuntime::<GolChallengeContract>::new();
+        let context = runtime.root_view_storage_context();
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let early_solver = AccountOwner::from(CryptoHash::test_hash("early solver"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        let unknown_puzzle_id = DataBlobHash(CryptoHash::test_hash("unknown"));
+        let board = Board::with_live_cells(4, vec![Position { x: 1, y: 1 }]);
+
+        let mut legacy = LegacyStateV0::load(context.clone())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        let solution = SolutionV0 {
+            board: board.clone(),
+            timestamp: Timestamp::from(10),
+            owner,
+        };
+        legacy.solutions.insert(&puzzle_id, solution).unwrap();
+        legacy.registered_puzzles.insert(&puzzle_id).unwrap();
+        legacy
+            .registered_puzzles
+            .insert(&unknown_puzzle_id)
+            .unwrap();
+        legacy.save().blocking_wait().unwrap();
+        report_legacy_solutions(
+            context.clone(),
+            &[(owner, puzzle_id, 20), (early_solver, puzzle_id, 15)],
+        );
+
+        let state = load_and_migrate(context.clone()).blocking_wait().unwrap();
+        assert_eq!(*state.schema_version.get(), SCHEMA_VERSION);
+        let solution = state
+            .solutions
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert_eq!(solution.board.live_positions(), board.live_positions());
+        assert_eq!(solution.owner, owner);
+        assert!(solution.scoring_statuses.is_empty());
+        // The registered puzzles must be registered again, after validating their blobs.
+        assert!(state
+            .registered_puzzles
+            .indices()
+            .blocking_wait()
+            .unwrap()
+            .is_empty());
+        let mut legacy_puzzles = state.legacy_puzzles.indices().blocking_wait().unwrap();
+        legacy_puzzles.sort();
+        let mut expected_puzzles = vec![puzzle_id, unknown_puzzle_id];
+        expected_puzzles.sort();
+        assert_eq!(legacy_puzzles, expected_puzzles);
+        assert!(state
+            .puzzle_catalog
+            .indices()
+            .blocking_wait()
+            .unwrap()
+            .is_empty());
+        // The legacy solvers are ranked by timestamp, and are not credited again when
+        // reporting their solutions.
+        let credited_solution = state
+            .reported_solutions
+            .try_load_entry(&owner)
+            .blocking_wait()
+            .unwrap()
+            .unwrap()
+            .get(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert_eq!(credited_solution.timestamp, Timestamp::from(20));
+        assert_eq!(credited_solution.rank, 2);
+        assert_eq!(credited_solution.points, 0);
+        let events = state
+            .solve_logs
+            .try_load_entry(&puzzle_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap()
+            .read(..)
+            .blocking_wait()
+            .unwrap()
+            .into_iter()
+            .map(|event| (event.owner, event.timestamp, event.rank))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                (early_solver, Timestamp::from(15), 1),
+                (owner, Timestamp::from(20), 2),
+            ]
+        );
+        // The next solver of the puzzle is ranked third.
+        assert_eq!(
+            state.solver_counts.get(&puzzle_id).blocking_wait().unwrap(),
+            Some(2)
+        );
+        let score = state
+            .scores
+            .owner_scores
+            .get(&owner)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert_eq!(score.points, 0);
+        assert_eq!(score.solved_puzzles, 1);
+        assert!(state
+            .solution_history
+            .indices()
+            .blocking_wait()
+            .unwrap()
+            .is_empty());
+
+        // Loading the migrated state again does not migrate it twice.
+        let state = load_and_migrate(context).blocking_wait().unwrap();
+        assert_eq!(*state.schema_version.get(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn legacy_solvers_are_credited_when_puzzles_are_registered_again() {
+        let runtime =
+            ContractRuntime::<GolChallengeContract>::new().with_system_time(Timestamp::from(1_000));
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let early_solver = AccountOwner::from(CryptoHash::test_hash("early solver"));
+        let puzzle_id = DataBlobHash(CryptoHash::test_hash("block"));
+        report_legacy_solutions(
+            runtime.root_view_storage_context(),
+            &[(owner, puzzle_id, 20), (early_solver, puzzle_id, 15)],
+        );
+        let puzzle = Puzzle {
+            title: "Block".to_string(),
+            summary: "Make a block".to_string(),
+            difficulty: Difficulty::Medium,
+            size: 4,
+            metadata: String::new(),
+            minimal_steps: 1,
+            maximal_steps: 1,
+            enforce_initial_conditions: false,
+            is_strict: false,
+            initial_conditions: Vec::new(),
+            final_conditions: vec![Condition::TestRectangle {
+                x_range: 1..3,
+                y_range: 1..3,
+                min_live_count: 4,
+                max_live_count: 4,
+            }],
+        };
+
+        let mut contract = GolChallengeContract::load(runtime).blocking_wait();
+        contract
+            .runtime
+            .add_expected_read_data_blob_requests(puzzle_id, bcs::to_bytes(&puzzle).unwrap());
+        contract.state.points_config.set(PointsConfig {
+            first_solver_bonus_points: 10,
+            ..PointsConfig::default()
+        });
+        contract
+            .execute_operation(Operation::RegisterPuzzle { puzzle_id })
+            .blocking_wait();
+
+        let state = &contract.state;
+        assert!(!state
+            .legacy_puzzles
+            .contains(&puzzle_id)
+            .blocking_wait()
+            .unwrap());
+        for (solver, points) in [(early_solver, 13), (owner, 3)] {
+            let score = state
+                .scores
+                .owner_scores
+                .get(&solver)
+                .blocking_wait()
+                .unwrap()
+                .unwrap();
+            assert_eq!(score.points, points);
+            assert_eq!(score.solved_puzzles, 1);
+            let credited_solution = state
+                .reported_solutions
+                .try_load_entry(&solver)
+                .blocking_wait()
+                .unwrap()
+                .unwrap()
+                .get(&puzzle_id)
+                .blocking_wait()
+                .unwrap()
+                .unwrap();
+            assert_eq!(credited_solution.points, points);
+        }
+    }
+
+    #[test]
+    fn migrate_with_missing_blobs() {
+        // The runtime has no blobs, so reading the blob of a registered puzzle would fail.
+        let runtime = ContractRuntime::<GolChallengeContract>::new();
+        let context = runtime.root_view_storage_context();
+        let missing_puzzle_id = DataBlobHash(CryptoHash::test_hash("missing"));
+        let mut legacy = LegacyStateV0::load(context)
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        legacy
+            .registered_puzzles
+            .insert(&missing_puzzle_id)
+            .unwrap();
+        legacy.save().blocking_wait().unwrap();
+
+        let contract = GolChallengeContract::load(runtime).blocking_wait();
+        let state = &contract.state;
+        assert_eq!(*state.schema_version.get(), SCHEMA_VERSION);
+        assert!(!state
+            .registered_puzzles
+            .contains(&missing_puzzle_id)
+            .blocking_wait()
+            .unwrap());
+        assert!(state
+            .legacy_puzzles
+            .contains(&missing_puzzle_id)
+            .blocking_wait()
+            .unwrap());
+    }
+
+    #[test]
+    fn migrate_empty_state() {
+        let runtime = ContractRuntime::<GolChallengeContract>::new();
+        let context = runtime.root_view_storage_context();
+        let state = load_and_migrate(context).blocking_wait().unwrap();
+        assert_eq!(*state.schema_version.get(), SCHEMA_VERSION);
+        assert!(state
+            .registered_puzzles
+            .indices()
+            .blocking_wait()
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::linera_base_types::AccountOwner;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Difficulty;
+
+/// How scoring chains award points for solutions to registered puzzles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "PointsConfigInput")]
+pub struct PointsConfig {
+    /// Points for solving a tutorial puzzle.
+    pub tutorial_points: u64,
+    /// Points for solving an easy puzzle.
+    pub easy_points: u64,
+    /// Points for solving a puzzle of medium difficulty.
+    pub medium_points: u64,
+    /// Points for solving a hard puzzle.
+    pub hard_points: u64,
+    /// Points for solving an expert puzzle.
+    pub expert_points: u64,
+    /// Maximal bonus for solving a puzzle soon after its registration. The bonus decreases
+    /// linearly until the end of the bonus period.
+    pub time_bonus_points: u64,
+    /// Duration of the time bonus period after the registration of a puzzle, in seconds.
+    pub time_bonus_period_secs: u64,
+    /// Bonus for being the first owner to solve a puzzle.
+    pub first_solver_bonus_points: u64,
+}
+
+impl Default for PointsConfig {
+    fn default() -> Self {
+        PointsConfig {
+            tutorial_points: 1,
+            easy_points: 2,
+            medium_points: 3,
+            hard_points: 5,
+            expert_points: 8,
+            time_bonus_points: 0,
+            time_bonus_period_secs: 0,
+            first_solver_bonus_points: 0,
+        }
+    }
+}
+
+impl PointsConfig {
+    /// The base points for solving a puzzle of the given difficulty.
+    pub fn difficulty_points(&self, difficulty: Difficulty) -> u64 {
+        match difficulty {
+            Difficulty::Tutorial => self.tutorial_points,
+            Difficulty::Easy => self.easy_points,
+            Difficulty::Medium => self.medium_points,
+            Difficulty::Hard => self.hard_points,
+            Difficulty::Expert => self.expert_points,
+        }
+    }
+
+    /// The time bonus for a solution found `elapsed_micros` after the puzzle was registered.
+    pub fn time_bonus(&self, elapsed_micros: u64) -> u64 {
+        let period_micros = u128::from(self.time_bonus_period_secs) * 1_000_000;
+        let elapsed_micros = u128::from(elapsed_micros);
+        if elapsed_micros >= period_micros {
+            return 0;
+        }
+        let bonus =
+            u128::from(self.time_bonus_points) * (period_micros - elapsed_micros) / period_micros;
+        bonus as u64
+    }
+
+    /// The total points for a solution.
+    pub fn points(
+        &self,
+        difficulty: Difficulty,
+        elapsed_micros: u64,
+        is_first_solver: bool,
+    ) -> u64 {
+        let mut points = self
+            .difficulty_points(difficulty)
+            .saturating_add(self.time_bonus(elapsed_micros));
+        if is_first_solver {
+            points = points.saturating_add(self.first_solver_bonus_points);
+        }
+        points
+    }
+}
+
+/// A key of the leaderboard index. Keys are serialized so that they are sorted by
+/// decreasing points, then by owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardKey {
+    /// The big-endian bytes of `u64::MAX - points`.
+    inverted_points: [u8; 8],
+    /// The owner with these points.
+    pub owner: AccountOwner,
+}
+
+impl LeaderboardKey {
+    /// Creates the leaderboard key of an owner with the given points.
+    pub fn new(owner: AccountOwner, points: u64) -> Self {
+        LeaderboardKey {
+            inverted_points: (u64::MAX - points).to_be_bytes(),
+            owner,
+        }
+    }
+
+    /// The points of the owner.
+    pub fn points(&self) -> u64 {
+        u64::MAX - u64::from_be_bytes(self.inverted_points)
+    }
+}
+
+/// An entry of a leaderboard.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct LeaderboardEntry {
+    /// The rank of the owner, starting at 1.
+    pub rank: u32,
+    /// The owner.
+    pub owner: AccountOwner,
+    /// The total number of points of the owner.
+    pub points: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difficulty_points() {
+        let config = PointsConfig::default();
+        assert_eq!(config.points(Difficulty::Tutorial, 0, true), 1);
+        assert_eq!(config.points(Difficulty::Expert, 0, false), 8);
+    }
+
+    #[test]
+    fn test_time_and_first_solver_bonus() {
+        let config = PointsConfig {
+            time_bonus_points: 10,
+            time_bonus_period_secs: 100,
+            first_solver_bonus_points: 7,
+            ..PointsConfig::default()
+        };
+        assert_eq!(config.time_bonus(0), 10);
+        assert_eq!(config.time_bonus(50_000_000), 5);
+        assert_eq!(config.time_bonus(99_999_999), 0);
+        assert_eq!(config.time_bonus(100_000_000), 0);
+        assert_eq!(config.points(Difficulty::Medium, 50_000_000, false), 8);
+        assert_eq!(config.points(Difficulty::Medium, 50_000_000, true), 15);
+    }
+}
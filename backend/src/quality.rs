@@ -0,0 +1,254 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{Enum, InputObject, SimpleObject};
+use linera_sdk::linera_base_types::AccountOwner;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Board, Position};
+
+/// An objective measure of the quality of a valid solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum QualityMetric {
+    /// The number of live cells. Lower is better.
+    LiveCells,
+    /// The area of the smallest rectangle containing the live cells. Lower is better.
+    BoundingBoxArea,
+    /// The number of steps after which the final conditions were met. Lower is better.
+    Steps,
+    /// The number of symmetries of the live cells. Higher is better.
+    Symmetry,
+}
+
+impl QualityMetric {
+    /// Whether a value of this metric is strictly better than another.
+    pub fn is_better(self, value: u32, other: u32) -> bool {
+        match self {
+            QualityMetric::Symmetry => value > other,
+            _ => value < other,
+        }
+    }
+}
+
+/// How the solvers of a puzzle are ranked by a scoring chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "QualityRankingInput")]
+pub struct QualityRanking {
+    /// The metric to rank solutions on.
+    pub metric: QualityMetric,
+    /// The value of the metric expected from a good solution, if any.
+    pub par: Option<u32>,
+}
+
+impl QualityRanking {
+    /// Whether the given metrics are at least as good as the par value. Always true if
+    /// there is no par value.
+    pub fn meets_par(&self, metrics: &QualityMetrics) -> bool {
+        match self.par {
+            Some(par) => !self.metric.is_better(par, metrics.value(self.metric)),
+            None => true,
+        }
+    }
+}
+
+/// The quality metrics of a valid solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct QualityMetrics {
+    /// The number of live cells.
+    pub live_cells: u32,
+    /// The area of the smallest rectangle containing the live cells.
+    pub bounding_box_area: u32,
+    /// The number of steps after which the final conditions were met.
+    pub steps: u16,
+    /// How many of the following transformations leave the live cells unchanged, within
+    /// their bounding box: horizontal reflection, vertical reflection, half-turn rotation
+    /// and diagonal reflection.
+    pub symmetries: u32,
+}
+
+impl QualityMetrics {
+    /// Computes the metrics of a board solving a puzzle in the given number of steps.
+    pub fn new(board: &Board, steps: u16) -> Self {
+        let cells = board.live_positions();
+        let Some(bounds) = Bounds::of(cells.iter()) else {
+            return QualityMetrics {
+                live_cells: 0,
+                bounding_box_area: 0,
+                steps,
+                symmetries: 0,
+            };
+        };
+        let transformations: [&dyn Fn(Position) -> Option<Position>; 4] = [
+            &|p| {
+                Some(Position {
+                    x: bounds.min_x + bounds.max_x - p.x,
+                    y: p.y,
+                })
+            },
+            &|p| {
+                Some(Position {
+                    x: p.x,
+                    y: bounds.min_y + bounds.max_y - p.y,
+                })
+            },
+            &|p| {
+                Some(Position {
+                    x: bounds.min_x + bounds.max_x - p.x,
+                    y: bounds.min_y + bounds.max_y - p.y,
+                })
+            },
+            &|p| {
+                (bounds.width() == bounds.height()).then(|| Position {
+                    x: bounds.min_x + p.y - bounds.min_y,
+                    y: bounds.min_y + p.x - bounds.min_x,
+                })
+            },
+        ];
+        let symmetries = transformations
+            .iter()
+            .filter(|transform| {
+                cells
+                    .iter()
+                    .all(|p| transform(*p).is_some_and(|image| cells.contains(&image)))
+            })
+            .count() as u32;
+        QualityMetrics {
+            live_cells: board.live_cell_count(),
+            bounding_box_area: bounds.width() * bounds.height(),
+            steps,
+            symmetries,
+        }
+    }
+
+    /// The value of the given metric.
+    pub fn value(&self, metric: QualityMetric) -> u32 {
+        match metric {
+            QualityMetric::LiveCells => self.live_cells,
+            QualityMetric::BoundingBoxArea => self.bounding_box_area,
+            QualityMetric::Steps => u32::from(self.steps),
+            QualityMetric::Symmetry => self.symmetries,
+        }
+    }
+}
+
+/// The smallest rectangle containing a non-empty set of positions, bounds included.
+struct Bounds {
+    min_x: u16,
+    max_x: u16,
+    min_y: u16,
+    max_y: u16,
+}
+
+impl Bounds {
+    fn of<'a>(mut positions: impl Iterator<Item = &'a Position>) -> Option<Self> {
+        let first = positions.next()?;
+        let mut bounds = Bounds {
+            min_x: first.x,
+            max_x: first.x,
+            min_y: first.y,
+            max_y: first.y,
+        };
+        for p in positions {
+            bounds.min_x = bounds.min_x.min(p.x);
+            bounds.max_x = bounds.max_x.max(p.x);
+            bounds.min_y = bounds.min_y.min(p.y);
+            bounds.max_y = bounds.max_y.max(p.y);
+        }
+        Some(bounds)
+    }
+
+    fn width(&self) -> u32 {
+        u32::from(self.max_x - self.min_x) + 1
+    }
+
+    fn height(&self) -> u32 {
+        u32::from(self.max_y - self.min_y) + 1
+    }
+}
+
+/// A key of the quality ranking of a puzzle. Keys are serialized so that they are sorted
+/// from the best value of the metric to the worst, then by owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QualityKey {
+    /// The big-endian bytes of the value, inverted if higher values are better.
+    ordered_value: [u8; 4],
+    /// The owner with this value.
+    pub owner: AccountOwner,
+}
+
+impl QualityKey {
+    /// Creates the ranking key of an owner with the given value of a metric.
+    pub fn new(owner: AccountOwner, metric: QualityMetric, value: u32) -> Self {
+        let ordered_value = match metric {
+            QualityMetric::Symmetry => u32::MAX - value,
+            _ => value,
+        };
+        QualityKey {
+            ordered_value: ordered_value.to_be_bytes(),
+            owner,
+        }
+    }
+}
+
+/// An entry of the quality ranking of a puzzle.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct QualityEntry {
+    /// The rank of the owner, starting at 1.
+    pub rank: u32,
+    /// The owner.
+    pub owner: AccountOwner,
+    /// The metrics of the best solution of the owner.
+    pub metrics: QualityMetrics,
+    /// Whether the best solution of the owner meets the par value of the puzzle.
+    pub meets_par: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(cells: &[(u16, u16)]) -> Board {
+        Board::with_live_cells(8, cells.iter().map(|&(x, y)| Position { x, y }).collect())
+    }
+
+    #[test]
+    fn test_quality_metrics() {
+        // A block is symmetric in every way.
+        let metrics = QualityMetrics::new(&board(&[(2, 2), (3, 2), (2, 3), (3, 3)]), 1);
+        assert_eq!(metrics.live_cells, 4);
+        assert_eq!(metrics.bounding_box_area, 4);
+        assert_eq!(metrics.symmetries, 4);
+
+        // An L-tromino has only a diagonal symmetry.
+        let metrics = QualityMetrics::new(&board(&[(4, 4), (5, 4), (4, 5)]), 4);
+        assert_eq!(metrics.live_cells, 3);
+        assert_eq!(metrics.bounding_box_area, 4);
+        assert_eq!(metrics.symmetries, 1);
+
+        // A horizontal blinker is symmetric but its bounding box is not square.
+        let metrics = QualityMetrics::new(&board(&[(1, 1), (2, 1), (3, 1)]), 2);
+        assert_eq!(metrics.bounding_box_area, 3);
+        assert_eq!(metrics.symmetries, 3);
+        assert_eq!(metrics.value(QualityMetric::Steps), 2);
+    }
+
+    #[test]
+    fn test_par() {
+        let metrics = QualityMetrics::new(&board(&[(1, 1), (2, 1), (3, 1)]), 2);
+        let ranking = QualityRanking {
+            metric: QualityMetric::LiveCells,
+            par: Some(3),
+        };
+        assert!(ranking.meets_par(&metrics));
+        let ranking = QualityRanking {
+            metric: QualityMetric::LiveCells,
+            par: Some(2),
+        };
+        assert!(!ranking.meets_par(&metrics));
+        let ranking = QualityRanking {
+            metric: QualityMetric::Symmetry,
+            par: Some(4),
+        };
+        assert!(!ranking.meets_par(&metrics));
+    }
+}
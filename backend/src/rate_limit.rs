@@ -0,0 +1,88 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// The maximal number of messages accepted in a sliding time window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "RateLimitInput")]
+pub struct RateLimit {
+    /// The maximal number of messages accepted during the window.
+    pub max_messages: u32,
+    /// The duration of the window, in seconds.
+    pub window_secs: u64,
+}
+
+/// The rate limits applied by a scoring chain to incoming solutions and votes.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject,
+)]
+#[graphql(input_name = "RateLimitsInput")]
+pub struct RateLimits {
+    /// The limit for the messages of each origin chain, if any.
+    pub per_chain: Option<RateLimit>,
+    /// The limit for the messages of each owner, if any.
+    pub per_owner: Option<RateLimit>,
+}
+
+/// The times of the messages recently accepted from a chain or an owner.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlidingWindow {
+    /// The times of the accepted messages, in increasing order.
+    timestamps: Vec<Timestamp>,
+}
+
+impl SlidingWindow {
+    /// Whether one more message is allowed at time `now`.
+    pub fn allows(&self, limit: &RateLimit, now: Timestamp) -> bool {
+        let start = Self::start(limit, now);
+        let count = self
+            .timestamps
+            .iter()
+            .filter(|timestamp| timestamp.micros() > start)
+            .count();
+        count < limit.max_messages as usize
+    }
+
+    /// Records a message accepted at time `now`, forgetting the messages that are
+    /// outside the window.
+    pub fn record(&mut self, limit: &RateLimit, now: Timestamp) {
+        let start = Self::start(limit, now);
+        self.timestamps
+            .retain(|timestamp| timestamp.micros() > start);
+        self.timestamps.push(now);
+    }
+
+    /// The start of the window ending at time `now`, excluded, in microseconds.
+    fn start(limit: &RateLimit, now: Timestamp) -> u64 {
+        now.micros()
+            .saturating_sub(limit.window_secs.saturating_mul(1_000_000))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sliding_window() {
+        let limit = RateLimit {
+            max_messages: 2,
+            window_secs: 10,
+        };
+        let secs = |secs: u64| Timestamp::from(secs * 1_000_000);
+        let mut window = SlidingWindow::default();
+        assert!(window.allows(&limit, secs(100)));
+        window.record(&limit, secs(100));
+        assert!(window.allows(&limit, secs(105)));
+        window.record(&limit, secs(105));
+        assert!(!window.allows(&limit, secs(109)));
+        // The first message leaves the window after 10 seconds.
+        assert!(window.allows(&limit, secs(110)));
+        window.record(&limit, secs(110));
+        assert_eq!(window.timestamps, vec![secs(105), secs(110)]);
+        assert!(!window.allows(&limit, secs(114)));
+    }
+}
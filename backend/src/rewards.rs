@@ -0,0 +1,43 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::linera_base_types::Amount;
+use serde::{Deserialize, Serialize};
+
+/// The token reward of a registered puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "PuzzleRewardInput")]
+pub struct PuzzleReward {
+    /// The reward of the first solver.
+    pub amount: Amount,
+    /// How much the reward decreases for each following solver.
+    pub decrement_per_solver: Amount,
+}
+
+impl PuzzleReward {
+    /// The reward of the solver with the given rank, starting at 1.
+    pub fn amount_for_rank(&self, rank: u32) -> Amount {
+        let decrement = self
+            .decrement_per_solver
+            .saturating_mul(u128::from(rank.saturating_sub(1)));
+        self.amount.saturating_sub(decrement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decaying_reward() {
+        let reward = PuzzleReward {
+            amount: Amount::from_tokens(10),
+            decrement_per_solver: Amount::from_tokens(3),
+        };
+        assert_eq!(reward.amount_for_rank(1), Amount::from_tokens(10));
+        assert_eq!(reward.amount_for_rank(2), Amount::from_tokens(7));
+        assert_eq!(reward.amount_for_rank(4), Amount::from_tokens(1));
+        assert_eq!(reward.amount_for_rank(5), Amount::ZERO);
+    }
+}
@@ -3,23 +3,44 @@
 
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
+mod legacy;
 mod state;
 
 use std::sync::Arc;
 
-use async_graphql::{ComplexObject, Context, EmptySubscription, Request, Response, Schema};
+use async_graphql::{
+    parser::{parse_query, types::OperationType},
+    ComplexObject, Context, EmptySubscription, Request, Response, Schema, ServerError,
+};
 use gol_challenge::{
+    achievements::Badge,
+    daily::{format_date, parse_date, DailyPuzzle},
+    day_index,
     game::{Board, Puzzle, ValidationResult},
+    points::LeaderboardEntry,
+    quality::QualityEntry,
+    solution_commitment,
+    teams::TeamEntry,
+    templates::PuzzleBlob,
     Operation,
 };
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::{DataBlobHash, WithServiceAbi},
+    linera_base_types::{AccountOwner, DataBlobHash, WithServiceAbi},
     views::View,
     Service, ServiceRuntime,
 };
 
-use self::state::GolChallengeState;
+use self::{
+    legacy::LegacyStateV0,
+    state::{
+        Bounty, GolChallengeState, Leaderboard, OwnerScore, Season, SolutionRecord, SolveEvent,
+        SCHEMA_VERSION,
+    },
+};
+
+/// The default number of entries returned by paginated queries.
+const DEFAULT_PAGE_SIZE: u32 = 100;
 
 #[derive(Clone)]
 pub struct GolChallengeService {
@@ -47,6 +68,14 @@ impl Service for GolChallengeService {
     }
 
     async fn handle_query(&self, request: Request) -> Response {
+        // Mutations remain available, so that the `migrateState` operation may be scheduled.
+        if self.is_outdated().await && !is_mutation(&request) {
+            return Response::from_errors(vec![ServerError::new(
+                "The state was written by a previous version of the application and must be \
+                migrated with the `migrateState` mutation",
+                None,
+            )]);
+        }
         let schema = Schema::build(
             self.state.clone(),
             Operation::mutation_root(self.runtime.clone()),
@@ -58,6 +87,31 @@ impl Service for GolChallengeService {
     }
 }
 
+impl GolChallengeService {
+    /// Whether the state was written by a previous version of the application and was not
+    /// migrated yet. The state of a chain where the application never ran is not outdated.
+    async fn is_outdated(&self) -> bool {
+        if *self.state.schema_version.get() >= SCHEMA_VERSION {
+            return false;
+        }
+        let legacy = LegacyStateV0::load(self.runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        !legacy.is_empty().await.expect("Failed to load state")
+    }
+}
+
+/// Whether a request only contains mutations.
+fn is_mutation(request: &Request) -> bool {
+    let Ok(document) = parse_query(&request.query) else {
+        return false;
+    };
+    document
+        .operations
+        .iter()
+        .all(|(_, operation)| operation.node.ty == OperationType::Mutation)
+}
+
 #[ComplexObject]
 impl GolChallengeState {
     /// Advance a board by one step using Conway's Game of Life rules.
@@ -70,19 +124,25 @@ impl GolChallengeState {
         board.advance(steps)
     }
 
-    /// Check if a board solves a puzzle.
+    /// Check if a board solves a puzzle. Puzzle templates are instantiated for the given
+    /// owner, if any.
     async fn validate_solution(
         &self,
         ctx: &Context<'_>,
         board: Board,
         puzzle_id: DataBlobHash,
+        owner: Option<AccountOwner>,
     ) -> ValidationResult {
         let runtime = ctx
             .data::<Arc<ServiceRuntime<GolChallengeService>>>()
             .unwrap();
         let puzzle_bytes = runtime.read_data_blob(puzzle_id);
-        let puzzle =
-            bcs::from_bytes::<Puzzle>(&puzzle_bytes).expect("Failed to deserialize puzzle");
+        let puzzle_blob =
+            PuzzleBlob::from_bytes(&puzzle_bytes).expect("Failed to deserialize puzzle");
+        let puzzle = match owner {
+            Some(owner) => puzzle_blob.into_puzzle_for(puzzle_id, &owner),
+            None => puzzle_blob.into_base_puzzle(),
+        };
 
         match puzzle.check_solution(&board) {
             Ok(steps) => ValidationResult {
@@ -108,13 +168,29 @@ impl GolChallengeState {
         format!("{:#}", board)
     }
 
-    /// Retrieve a puzzle by its ID.
-    async fn puzzle(&self, ctx: &Context<'_>, puzzle_id: DataBlobHash) -> Option<Puzzle> {
+    /// Compute the commitment to a solution, to be sent with `commitSolution` before
+    /// revealing the solution with `revealSolution`. The salt must remain secret until then.
+    async fn solution_commitment(&self, board: Board, owner: AccountOwner, salt: String) -> String {
+        solution_commitment(&board, &owner, &salt)
+    }
+
+    /// Retrieve a puzzle by its ID. Puzzle templates are instantiated for the given owner,
+    /// or else return their base puzzle.
+    async fn puzzle(
+        &self,
+        ctx: &Context<'_>,
+        puzzle_id: DataBlobHash,
+        owner: Option<AccountOwner>,
+    ) -> Option<Puzzle> {
         let runtime = ctx
             .data::<Arc<ServiceRuntime<GolChallengeService>>>()
             .unwrap();
         let puzzle_bytes = runtime.read_data_blob(puzzle_id);
-        bcs::from_bytes::<Puzzle>(&puzzle_bytes).ok()
+        let puzzle_blob = PuzzleBlob::from_bytes(&puzzle_bytes).ok()?;
+        match owner {
+            Some(owner) => Some(puzzle_blob.into_puzzle_for(puzzle_id, &owner)),
+            None => Some(puzzle_blob.into_base_puzzle()),
+        }
     }
 
     /// Print the ASCII representation of a puzzle given by its ID.
@@ -122,8 +198,9 @@ impl GolChallengeState {
         &self,
         ctx: &Context<'_>,
         puzzle_id: DataBlobHash,
+        owner: Option<AccountOwner>,
     ) -> Result<Option<String>, async_graphql::Error> {
-        let Some(puzzle) = self.puzzle(ctx, puzzle_id).await? else {
+        let Some(puzzle) = self.puzzle(ctx, puzzle_id, owner).await? else {
             return Ok(None);
         };
         Ok(Some(format!("{}", puzzle)))
@@ -134,12 +211,338 @@ impl GolChallengeState {
         &self,
         ctx: &Context<'_>,
         puzzle_id: DataBlobHash,
+        owner: Option<AccountOwner>,
     ) -> Result<Option<String>, async_graphql::Error> {
-        let Some(puzzle) = self.puzzle(ctx, puzzle_id).await? else {
+        let Some(puzzle) = self.puzzle(ctx, puzzle_id, owner).await? else {
             return Ok(None);
         };
         Ok(Some(format!("{:#}", puzzle)))
     }
+
+    /// The leaderboard of this scoring chain, sorted by decreasing points.
+    async fn leaderboard(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<Vec<LeaderboardEntry>, async_graphql::Error> {
+        self.scores.entries(offset, limit).await
+    }
+
+    /// The teams of this scoring chain, sorted by decreasing score.
+    async fn team_leaderboard(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<Vec<TeamEntry>, async_graphql::Error> {
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+        let mut entries = Vec::new();
+        if limit == 0 {
+            return Ok(entries);
+        }
+        let mut rank = 0;
+        self.sorted_teams
+            .for_each_index_while(|key| {
+                rank += 1;
+                if rank > offset {
+                    entries.push(TeamEntry {
+                        rank,
+                        score: key.score(),
+                        name: key.name,
+                    });
+                }
+                Ok(entries.len() < limit)
+            })
+            .await?;
+        Ok(entries)
+    }
+
+    /// The best solution of an owner to a puzzle among the solutions submitted on this
+    /// chain, according to the current criterion.
+    async fn best_solution(
+        &self,
+        owner: AccountOwner,
+        puzzle_id: DataBlobHash,
+    ) -> Result<Option<SolutionRecord>, async_graphql::Error> {
+        let Some(best_solutions) = self.best_solutions.try_load_entry(&owner).await? else {
+            return Ok(None);
+        };
+        let criterion = *self.best_solution_criterion.get();
+        Ok(best_solutions.get(&(puzzle_id, criterion)).await?)
+    }
+
+    /// The owner credited with the best rank for a registered puzzle on this scoring
+    /// chain.
+    async fn first_solver(
+        &self,
+        puzzle_id: DataBlobHash,
+    ) -> Result<Option<SolveEvent>, async_graphql::Error> {
+        let Some(solve_log) = self.solve_logs.try_load_entry(&puzzle_id).await? else {
+            return Ok(None);
+        };
+        let events = solve_log.read(..).await?;
+        Ok(events.into_iter().min_by_key(|event| event.rank))
+    }
+
+    /// The solve event of an owner for a registered puzzle on this scoring chain.
+    async fn solve_event(
+        &self,
+        puzzle_id: DataBlobHash,
+        owner: AccountOwner,
+    ) -> Result<Option<SolveEvent>, async_graphql::Error> {
+        let Some(solved_puzzles) = self.reported_solutions.try_load_entry(&owner).await? else {
+            return Ok(None);
+        };
+        let Some(credited_solution) = solved_puzzles.get(&puzzle_id).await? else {
+            return Ok(None);
+        };
+        Ok(Some(SolveEvent {
+            owner,
+            timestamp: credited_solution.timestamp,
+            rank: credited_solution.rank,
+        }))
+    }
+
+    /// The score of an owner on this scoring chain.
+    async fn owner_score(
+        &self,
+        owner: AccountOwner,
+    ) -> Result<Option<OwnerScore>, async_graphql::Error> {
+        Ok(self.scores.owner_scores.get(&owner).await?)
+    }
+
+    /// The seasons running at the current time.
+    async fn current_seasons(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<Season>, async_graphql::Error> {
+        let runtime = ctx
+            .data::<Arc<ServiceRuntime<GolChallengeService>>>()
+            .unwrap();
+        let now = runtime.system_time();
+        self.seasons_matching(|season| season.contains(now)).await
+    }
+
+    /// The bounties that may still be claimed by solving their puzzle.
+    async fn active_bounties(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<Bounty>, async_graphql::Error> {
+        let runtime = ctx
+            .data::<Arc<ServiceRuntime<GolChallengeService>>>()
+            .unwrap();
+        let now = runtime.system_time();
+        let mut bounties = Vec::new();
+        for puzzle_id in self.bounties.indices().await? {
+            if let Some(bounty) = self.bounties.get(&puzzle_id).await? {
+                if now < bounty.expiry {
+                    bounties.push(bounty);
+                }
+            }
+        }
+        Ok(bounties)
+    }
+
+    /// The puzzle of the given day (`YYYY-MM-DD`, UTC), or of today, if any.
+    async fn daily_puzzle(
+        &self,
+        ctx: &Context<'_>,
+        date: Option<String>,
+    ) -> Result<Option<DailyPuzzle>, async_graphql::Error> {
+        let day = match date {
+            Some(date) => parse_date(&date).ok_or("Invalid date, expected YYYY-MM-DD")?,
+            None => {
+                let runtime = ctx
+                    .data::<Arc<ServiceRuntime<GolChallengeService>>>()
+                    .unwrap();
+                day_index(runtime.system_time())
+            }
+        };
+        let puzzle = self
+            .daily_puzzles
+            .get(&day)
+            .await?
+            .map(|puzzle_id| DailyPuzzle {
+                date: format_date(day),
+                puzzle_id,
+            });
+        Ok(puzzle)
+    }
+
+    /// The seasons that have ended.
+    async fn past_seasons(&self, ctx: &Context<'_>) -> Result<Vec<Season>, async_graphql::Error> {
+        let runtime = ctx
+            .data::<Arc<ServiceRuntime<GolChallengeService>>>()
+            .unwrap();
+        let now = runtime.system_time();
+        self.seasons_matching(|season| season.end <= now).await
+    }
+
+    /// The standings of a season, sorted by decreasing points.
+    async fn season_standings(
+        &self,
+        name: String,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<Vec<LeaderboardEntry>, async_graphql::Error> {
+        match self.season_scores.try_load_entry(&name).await? {
+            Some(leaderboard) => leaderboard.entries(offset, limit).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The solvers of a puzzle with a quality ranking, sorted from the best solution to
+    /// the worst.
+    async fn quality_standings(
+        &self,
+        puzzle_id: DataBlobHash,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<Vec<QualityEntry>, async_graphql::Error> {
+        let Some(ranking) = self.puzzle_rankings.get(&puzzle_id).await? else {
+            return Ok(Vec::new());
+        };
+        let Some(quality_board) = self.quality_boards.try_load_entry(&puzzle_id).await? else {
+            return Ok(Vec::new());
+        };
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+        let mut owners = Vec::new();
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut rank = 0;
+        quality_board
+            .sorted_owners
+            .for_each_index_while(|key| {
+                rank += 1;
+                if rank > offset {
+                    owners.push((rank, key.owner));
+                }
+                Ok(owners.len() < limit)
+            })
+            .await?;
+        let mut entries = Vec::new();
+        for (rank, owner) in owners {
+            let metrics = quality_board
+                .owner_metrics
+                .get(&owner)
+                .await?
+                .expect("Ranked owners should have metrics");
+            entries.push(QualityEntry {
+                rank,
+                owner,
+                meets_par: ranking.meets_par(&metrics),
+                metrics,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// The achievements earned by an owner, in alphabetical order.
+    async fn badges(&self, owner: AccountOwner) -> Result<Vec<Badge>, async_graphql::Error> {
+        let Some(earned) = self.earned_achievements.try_load_entry(&owner).await? else {
+            return Ok(Vec::new());
+        };
+        let mut badges = Vec::new();
+        for (name, earned_at) in earned.index_values().await? {
+            let achievement = self
+                .achievements
+                .get(&name)
+                .await?
+                .expect("Earned achievements should exist");
+            badges.push(Badge {
+                name,
+                description: achievement.description,
+                earned_at,
+            });
+        }
+        Ok(badges)
+    }
+
+    /// The puzzles of the campaigns whose prerequisites were solved by an owner.
+    async fn unlocked_puzzles(
+        &self,
+        owner: AccountOwner,
+    ) -> Result<Vec<DataBlobHash>, async_graphql::Error> {
+        self.campaign_puzzles_matching(owner, true).await
+    }
+
+    /// The puzzles of the campaigns whose prerequisites were not solved by an owner yet.
+    async fn locked_puzzles(
+        &self,
+        owner: AccountOwner,
+    ) -> Result<Vec<DataBlobHash>, async_graphql::Error> {
+        self.campaign_puzzles_matching(owner, false).await
+    }
+}
+
+impl GolChallengeState {
+    async fn campaign_puzzles_matching(
+        &self,
+        owner: AccountOwner,
+        is_unlocked: bool,
+    ) -> Result<Vec<DataBlobHash>, async_graphql::Error> {
+        let mut puzzle_ids = Vec::new();
+        for name in self.campaigns.indices().await? {
+            let Some(campaign) = self.campaigns.get(&name).await? else {
+                continue;
+            };
+            for puzzle in campaign.puzzles {
+                if self.is_unlocked(owner, puzzle.puzzle_id).await? == is_unlocked {
+                    puzzle_ids.push(puzzle.puzzle_id);
+                }
+            }
+        }
+        Ok(puzzle_ids)
+    }
+
+    async fn seasons_matching(
+        &self,
+        predicate: impl Fn(&Season) -> bool,
+    ) -> Result<Vec<Season>, async_graphql::Error> {
+        let mut seasons = Vec::new();
+        for name in self.seasons.indices().await? {
+            if let Some(season) = self.seasons.get(&name).await? {
+                if predicate(&season) {
+                    seasons.push(season);
+                }
+            }
+        }
+        Ok(seasons)
+    }
+}
+
+#[ComplexObject]
+impl Leaderboard {
+    /// The owners sorted by decreasing points.
+    async fn entries(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<Vec<LeaderboardEntry>, async_graphql::Error> {
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+        let mut entries = Vec::new();
+        if limit == 0 {
+            return Ok(entries);
+        }
+        let mut rank = 0;
+        self.sorted_scores
+            .for_each_index_while(|key| {
+                rank += 1;
+                if rank > offset {
+                    entries.push(LeaderboardEntry {
+                        rank,
+                        points: key.points(),
+                        owner: key.owner,
+                    });
+                }
+                Ok(entries.len() < limit)
+            })
+            .await?;
+        Ok(entries)
+    }
 }
 
 #[cfg(test)]
@@ -354,6 +757,91 @@ advanceBoardOnce(board: {size: 3, liveCells: [ {x: 1, y: 1}, {x: 1, y: 0}, {x: 1
         assert!(result["errorDetails"].is_object());
     }
 
+    #[test]
+    fn query_puzzle_template() {
+        use gol_challenge::{
+            game::{Condition, Difficulty, Position, Puzzle},
+            templates::PuzzleTemplate,
+        };
+
+        let runtime = ServiceRuntime::<GolChallengeService>::new();
+        let state = GolChallengeState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+
+        let service = GolChallengeService {
+            state: Arc::new(state),
+            runtime: Arc::new(runtime),
+        };
+
+        // A single cell that dies after 1 step, anywhere in the top-left corner.
+        let template = PuzzleTemplate {
+            puzzle: Puzzle {
+                title: "Single Cell Death".to_string(),
+                summary: "A single cell should die after one step".to_string(),
+                difficulty: Difficulty::Easy,
+                size: 5,
+                metadata: String::new(),
+                minimal_steps: 1,
+                maximal_steps: 1,
+                enforce_initial_conditions: true,
+                is_strict: false,
+                initial_conditions: vec![Condition::TestPosition {
+                    position: Position { x: 0, y: 0 },
+                    is_live: true,
+                }],
+                final_conditions: vec![Condition::TestPosition {
+                    position: Position { x: 0, y: 0 },
+                    is_live: false,
+                }],
+            },
+            max_offset_x: 3,
+            max_offset_y: 3,
+            allow_mirroring: false,
+        };
+        let template_bytes = template.to_bytes().expect("Failed to serialize template");
+        let puzzle_id = DataBlobHash(CryptoHash::new(&BlobContent::new_data(
+            template_bytes.clone(),
+        )));
+        service.runtime.set_blob(puzzle_id, template_bytes);
+
+        let blob_hash = puzzle_id.0;
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let instance = template.instance(puzzle_id, &owner);
+        let Condition::TestPosition { position, .. } = instance.initial_conditions[0] else {
+            panic!("Unexpected condition");
+        };
+        let response = service
+            .handle_query(Request::new(format!(
+                r#"{{
+                    puzzle(puzzleId: "{blob_hash}", owner: "{owner}") {{
+                        initialConditions
+                    }}
+                    validateSolution(
+                        board: {{size: 5, liveCells: [{{x: {}, y: {}}}]}},
+                        puzzleId: "{blob_hash}",
+                        owner: "{owner}"
+                    ) {{
+                        isValidAfterSteps
+                    }}
+                }}"#,
+                position.x, position.y,
+            )))
+            .now_or_never()
+            .expect("Query should not await anything")
+            .data
+            .into_json()
+            .expect("Response should be JSON");
+
+        assert_eq!(
+            response,
+            json!({
+                "puzzle": { "initialConditions": instance.initial_conditions },
+                "validateSolution": { "isValidAfterSteps": 1 }
+            })
+        );
+    }
+
     #[test]
     fn query_print_board() {
         let runtime = ServiceRuntime::<GolChallengeService>::new();
@@ -614,4 +1102,108 @@ advanceBoardOnce(board: {size: 3, liveCells: [ {x: 1, y: 1}, {x: 1, y: 0}, {x: 1
         // Should contain the legend information
         assert!(puzzle_string.contains("◦ [0-1, 0-1] 1-3 live cells"));
     }
+
+    #[test]
+    fn query_solution_commitment() {
+        use gol_challenge::game::Position;
+
+        let runtime = ServiceRuntime::<GolChallengeService>::new();
+        let state = GolChallengeState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+
+        let service = GolChallengeService {
+            state: Arc::new(state),
+            runtime: Arc::new(runtime),
+        };
+
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let board = Board::with_live_cells(3, vec![Position { x: 1, y: 1 }]);
+        let query_commitment = |salt: &str| {
+            let query = format!(
+                r#"{{
+                    solutionCommitment(
+                        board: {{size: 3, liveCells: [{{x: 1, y: 1}}]}},
+                        owner: "{owner}",
+                        salt: "{salt}"
+                    )
+                }}"#
+            );
+            service
+                .handle_query(Request::new(query))
+                .now_or_never()
+                .expect("Query should not await anything")
+                .data
+                .into_json()
+                .expect("Response should be JSON")["solutionCommitment"]
+                .clone()
+        };
+
+        let commitment = solution_commitment(&board, &owner, "secret");
+        assert_eq!(commitment.len(), 64);
+        assert_eq!(query_commitment("secret"), json!(commitment));
+        // The commitment depends on the salt, the owner and the board.
+        assert_ne!(query_commitment("other secret"), json!(commitment));
+        let other_owner = AccountOwner::from(CryptoHash::test_hash("other owner"));
+        assert_ne!(
+            solution_commitment(&board, &other_owner, "secret"),
+            commitment
+        );
+        let other_board = Board::with_live_cells(3, vec![Position { x: 0, y: 1 }]);
+        assert_ne!(
+            solution_commitment(&other_board, &owner, "secret"),
+            commitment
+        );
+    }
+
+    #[test]
+    fn query_leaderboard() {
+        use gol_challenge::points::LeaderboardKey;
+
+        let runtime = ServiceRuntime::<GolChallengeService>::new();
+        let mut state = GolChallengeState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+
+        let owners =
+            ["alice", "bob", "carol"].map(|name| AccountOwner::from(CryptoHash::test_hash(name)));
+        for (owner, points) in owners.iter().zip([5, 12, 7]) {
+            state
+                .scores
+                .sorted_scores
+                .insert(&LeaderboardKey::new(*owner, points))
+                .expect("Failed to update the leaderboard");
+        }
+
+        let service = GolChallengeService {
+            state: Arc::new(state),
+            runtime: Arc::new(runtime),
+        };
+
+        let response = service
+            .handle_query(Request::new(
+                "{
+                    leaderboard(offset: 1, limit: 5) {
+                        rank
+                        owner
+                        points
+                    }
+                }",
+            ))
+            .now_or_never()
+            .expect("Query should not await anything")
+            .data
+            .into_json()
+            .expect("Response should be JSON");
+
+        assert_eq!(
+            response,
+            json!({
+                "leaderboard": [
+                    { "rank": 2, "owner": owners[2], "points": 7 },
+                    { "rank": 3, "owner": owners[0], "points": 5 }
+                ]
+            })
+        );
+    }
 }
@@ -1,14 +1,36 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use async_graphql::{InputObject, SimpleObject};
-use gol_challenge::game::Board;
+use async_graphql::{Enum, InputObject, SimpleObject};
+use gol_challenge::{
+    achievements::{Achievement, DifficultyCounts},
+    campaign::CampaignPuzzle,
+    daily::{DailyConfig, DailyStreak},
+    duel::{DuelId, DuelOutcome},
+    game::{Board, Difficulty},
+    immigration::{GameId, ImmigrationGame},
+    points::{LeaderboardKey, PointsConfig},
+    quality::{QualityKey, QualityMetric, QualityMetrics, QualityRanking},
+    rate_limit::{RateLimits, SlidingWindow},
+    rewards::PuzzleReward,
+    teams::{TeamKey, TeamPolicy},
+    votes::VoteSummary,
+    SolutionCriterion,
+};
 use linera_sdk::{
-    linera_base_types::{AccountOwner, DataBlobHash, Timestamp},
-    views::{linera_views, CollectionView, MapView, RootView, SetView, ViewStorageContext},
+    linera_base_types::{
+        Account, AccountOwner, Amount, ChainId, CryptoHash, DataBlobHash, Timestamp,
+    },
+    views::{
+        linera_views, CollectionView, LogView, MapView, RegisterView, RootView, SetView, View,
+        ViewError, ViewStorageContext,
+    },
 };
 use serde::{Deserialize, Serialize};
 
+/// The version of the state layout written by this contract.
+pub const SCHEMA_VERSION: u32 = 1;
+
 /// The application state.
 #[derive(RootView, SimpleObject)]
 #[graphql(complex)]
@@ -18,13 +40,204 @@ pub struct GolChallengeState {
     /// The local solutions previously submitted by an owner of the chain. Puzzles do not
     /// need to be registered.
     pub solutions: MapView<DataBlobHash, Solution>,
+    /// Every solution submitted on this chain, indexed by puzzle, in order of submission.
+    pub solution_history: CollectionView<DataBlobHash, LogView<SolutionRecord>>,
+    /// How the best solution of each owner and puzzle is chosen.
+    pub best_solution_criterion: RegisterView<SolutionCriterion>,
+    /// The best solution of each owner for each puzzle, according to each criterion, so
+    /// that changing `best_solution_criterion` does not require to scan the history.
+    #[graphql(skip)]
+    pub best_solutions:
+        CollectionView<AccountOwner, MapView<(DataBlobHash, SolutionCriterion), SolutionRecord>>,
+    /// The duels of the owners of this chain, indexed by ID.
+    pub duels: MapView<DuelId, Duel>,
+    /// The number of duels created on this chain.
+    pub duel_count: RegisterView<u32>,
+    /// The results of the duels of this chain, for each player.
+    pub duel_records: MapView<AccountOwner, DuelRecord>,
+    /// The Immigration games of the owners of this chain, indexed by ID.
+    pub immigration_games: MapView<GameId, ImmigrationMatch>,
+    /// The number of Immigration games created on this chain.
+    pub immigration_game_count: RegisterView<u32>,
 
     // Scoring chains only.
     /// The set of registered puzzles.
     pub registered_puzzles: SetView<DataBlobHash>,
+    /// The decoded information of the registered puzzles.
+    pub puzzle_catalog: MapView<DataBlobHash, RegisteredPuzzle>,
+    /// Whether reported solutions are verified again before being accepted.
+    pub verify_reported_solutions: RegisterView<bool>,
     /// The set of all solutions reported to us, indexed by owner, then by puzzle_id. We only track
     /// registered puzzles.
-    pub reported_solutions: CollectionView<AccountOwner, MapView<DataBlobHash, Timestamp>>,
+    pub reported_solutions: CollectionView<AccountOwner, MapView<DataBlobHash, CreditedSolution>>,
+    /// How points are awarded for new solutions.
+    pub points_config: RegisterView<PointsConfig>,
+    /// The owners credited for each registered puzzle, in order of crediting. Solutions
+    /// revealed after a commitment are ranked from the time of the commitment.
+    pub solve_logs: CollectionView<DataBlobHash, LogView<SolveEvent>>,
+    /// The scores of all owners.
+    pub scores: Leaderboard,
+    /// The quality ranking of the registered puzzles declaring one in their blob.
+    pub puzzle_rankings: MapView<DataBlobHash, QualityRanking>,
+    /// The solvers of the puzzles with a quality ranking, ranked by their best solution.
+    pub quality_boards: CollectionView<DataBlobHash, QualityBoard>,
+    /// The seasons of this scoring chain, indexed by name.
+    pub seasons: MapView<String, Season>,
+    /// The scores of each season.
+    pub season_scores: CollectionView<String, Leaderboard>,
+    /// The solutions credited in each season, by owner and puzzle.
+    #[graphql(skip)]
+    pub season_solutions: CollectionView<String, SetView<(AccountOwner, DataBlobHash)>>,
+    /// The campaigns of this scoring chain, indexed by name.
+    pub campaigns: MapView<String, Campaign>,
+    /// The name of the campaign of each puzzle belonging to one.
+    pub puzzle_campaigns: MapView<DataBlobHash, String>,
+    /// The tokens left in the reward pool.
+    pub reward_pool_balance: RegisterView<Amount>,
+    /// The token rewards of registered puzzles.
+    pub puzzle_rewards: MapView<DataBlobHash, PuzzleReward>,
+    /// The history of the rewards paid.
+    pub reward_payouts: LogView<RewardPayout>,
+    /// The bounties posted by users and not yet paid or reclaimed, indexed by puzzle.
+    pub bounties: MapView<DataBlobHash, Bounty>,
+    /// The history of the bounties paid to solvers.
+    pub bounty_payouts: LogView<RewardPayout>,
+    /// The solution commitments received from user chains and not revealed yet, indexed by
+    /// the authenticated owner and the commitment.
+    #[graphql(skip)]
+    pub solution_commits: MapView<(AccountOwner, String), SolutionCommit>,
+    /// The aggregated votes on registered puzzles, from owners credited for them.
+    pub puzzle_votes: MapView<DataBlobHash, VoteSummary>,
+    /// The owners who voted on each puzzle.
+    #[graphql(skip)]
+    pub puzzle_voters: CollectionView<DataBlobHash, SetView<AccountOwner>>,
+    /// The rate limits of incoming solutions, commitments and votes.
+    pub rate_limits: RegisterView<RateLimits>,
+    /// The messages recently accepted from each origin chain.
+    #[graphql(skip)]
+    pub chain_windows: MapView<ChainId, SlidingWindow>,
+    /// The messages recently accepted from each owner.
+    #[graphql(skip)]
+    pub owner_windows: MapView<AccountOwner, SlidingWindow>,
+    /// The number of messages rejected by the rate limit of each origin chain.
+    pub rate_limited_chains: MapView<ChainId, u64>,
+    /// The number of messages rejected by the rate limit of each owner.
+    pub rate_limited_owners: MapView<AccountOwner, u64>,
+
+    /// The version of the layout of this state. See `SCHEMA_VERSION`. Fields are stored
+    /// by position, so new fields go after this one.
+    pub schema_version: RegisterView<u32>,
+
+    // Scoring chains only.
+    /// The achievements of this scoring chain, indexed by name.
+    pub achievements: MapView<String, Achievement>,
+    /// The achievements earned by each owner, with the time they were earned.
+    pub earned_achievements: CollectionView<AccountOwner, MapView<String, Timestamp>>,
+
+    // User chains only.
+    /// The nonces of the owner consents used on this chain.
+    #[graphql(skip)]
+    pub used_consents: SetView<(AccountOwner, u64)>,
+
+    // Scoring chains only.
+    /// The teams of this scoring chain, indexed by name.
+    pub teams: MapView<String, Team>,
+    /// The name of the team of each member.
+    pub owner_teams: MapView<AccountOwner, String>,
+    /// The distinct puzzles solved by the members of each team.
+    #[graphql(skip)]
+    pub team_puzzles: CollectionView<String, SetView<DataBlobHash>>,
+    /// The teams sorted by decreasing score.
+    #[graphql(skip)]
+    pub sorted_teams: SetView<TeamKey>,
+    /// The puzzle of each scheduled day. See `day_index`.
+    pub daily_puzzles: MapView<u64, DataBlobHash>,
+    /// The first day after the daily schedule.
+    pub daily_schedule_end: RegisterView<u64>,
+    /// How streak points are awarded for solving the puzzle of the day.
+    pub daily_config: RegisterView<DailyConfig>,
+    /// The streak of each owner solving the puzzle of the day.
+    pub daily_streaks: MapView<AccountOwner, DailyStreak>,
+
+    // User and scoring chains.
+    /// The puzzles published with a reference solution, on the chain of their creator and
+    /// on scoring chains.
+    pub verified_puzzles: MapView<DataBlobHash, VerifiedPuzzle>,
+
+    // Scoring chains only.
+    /// The number of owners credited for each registered puzzle.
+    #[graphql(skip)]
+    pub solver_counts: MapView<DataBlobHash, u32>,
+    /// The latest reveal deadline of the solution commitments to each puzzle. Until then,
+    /// bounties may only be claimed by revealed solutions.
+    pub commit_deadlines: MapView<DataBlobHash, Timestamp>,
+    /// The puzzles registered by the first version of the application, whose blobs were
+    /// not validated. They are not scored until registered again.
+    pub legacy_puzzles: SetView<DataBlobHash>,
+    /// The number of registered puzzles of each difficulty.
+    pub registered_counts: MapView<Difficulty, u32>,
+    /// The points credited to the creator of each published puzzle.
+    pub publication_points: RegisterView<u64>,
+    /// The puzzles of the daily schedule. Each puzzle is scheduled at most once.
+    pub scheduled_puzzles: SetView<DataBlobHash>,
+    /// The number of registered puzzles credited to each owner, by difficulty.
+    #[graphql(skip)]
+    pub solved_counts: MapView<AccountOwner, DifficultyCounts>,
+    /// The number of registered puzzles credited to each owner for the solutions of each
+    /// day, by difficulty. See `day_index`.
+    #[graphql(skip)]
+    pub daily_solved_counts: MapView<(AccountOwner, u64), DifficultyCounts>,
+}
+
+impl GolChallengeState {
+    /// Whether an owner solved the prerequisites of a puzzle in its campaign, if any.
+    pub async fn is_unlocked(
+        &self,
+        owner: AccountOwner,
+        puzzle_id: DataBlobHash,
+    ) -> Result<bool, ViewError> {
+        let Some(name) = self.puzzle_campaigns.get(&puzzle_id).await? else {
+            return Ok(true);
+        };
+        let campaign = self
+            .campaigns
+            .get(&name)
+            .await?
+            .expect("Campaigns of puzzles should exist");
+        let prerequisites = campaign.prerequisites(puzzle_id);
+        let Some(solved_puzzles) = self.reported_solutions.try_load_entry(&owner).await? else {
+            return Ok(prerequisites.is_empty());
+        };
+        for prerequisite in prerequisites {
+            if !solved_puzzles.contains_key(prerequisite).await? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// The running scores of owners, with an index sorted by decreasing points.
+#[derive(View, SimpleObject)]
+#[graphql(complex)]
+#[view(context = ViewStorageContext)]
+pub struct Leaderboard {
+    /// The running score of each owner.
+    pub owner_scores: MapView<AccountOwner, OwnerScore>,
+    /// The owners sorted by decreasing points.
+    #[graphql(skip)]
+    pub sorted_scores: SetView<LeaderboardKey>,
+}
+
+/// The solvers of a puzzle, with an index sorted from the best solution to the worst.
+#[derive(View, SimpleObject)]
+#[view(context = ViewStorageContext)]
+pub struct QualityBoard {
+    /// The metrics of the best solution of each owner.
+    pub owner_metrics: MapView<AccountOwner, QualityMetrics>,
+    /// The owners sorted from the best solution to the worst.
+    #[graphql(skip)]
+    pub sorted_owners: SetView<QualityKey>,
 }
 
 /// A verified solution to a GoL puzzle.
@@ -36,4 +249,275 @@ pub struct Solution {
     pub timestamp: Timestamp,
     /// The user credited for the solution.
     pub owner: AccountOwner,
+    /// The status of the solution on each scoring chain it was reported to.
+    pub scoring_statuses: Vec<ScoringStatus>,
+}
+
+/// A solution submitted on a user chain, with the metrics used to choose the best
+/// solutions.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SolutionRecord {
+    /// The initial state of the board solving the puzzle.
+    pub board: Board,
+    /// Timestamp of the submission.
+    pub timestamp: Timestamp,
+    /// The user credited for the solution.
+    pub owner: AccountOwner,
+    /// The number of steps after which the final conditions were met.
+    pub steps: u16,
+    /// The number of live cells of the board.
+    pub live_cells: u32,
+}
+
+/// The status of a solution reported to a scoring chain.
+#[derive(Debug, Clone, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "ScoringStatusInput")]
+pub struct ScoringStatus {
+    /// The scoring chain.
+    pub chain_id: ChainId,
+    /// The outcome of the solution, or `None` until the scoring chain replies. Solutions
+    /// rejected by a rate limit get no reply.
+    pub outcome: Option<ScoringOutcome>,
+}
+
+/// How a scoring chain handled a reported solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ScoringOutcome {
+    /// The solution was credited.
+    Credited,
+    /// The puzzle is not registered on the scoring chain.
+    Ignored,
+    /// The owner was already credited for this puzzle.
+    Duplicate,
+    /// The solution failed verification on the scoring chain.
+    Rejected,
+    /// The owner has not solved the prerequisites of the puzzle in its campaign yet.
+    Locked,
+}
+
+/// The information on a registered puzzle, decoded from its blob at registration time.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RegisteredPuzzle {
+    /// The title of the puzzle.
+    pub title: String,
+    /// The difficulty level, according to the puzzle's creator.
+    pub difficulty: Difficulty,
+    /// The grid size.
+    pub size: u16,
+    /// When the puzzle was first registered.
+    pub registered_at: Timestamp,
+}
+
+/// A puzzle published with a reference solution proving that it is solvable.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VerifiedPuzzle {
+    /// The owner that published the puzzle.
+    pub creator: AccountOwner,
+    /// The hash of the reference solution. See `board_hash`.
+    pub solution_hash: CryptoHash,
+    /// When the puzzle was published.
+    pub published_at: Timestamp,
+}
+
+/// An owner credited for a registered puzzle on a scoring chain.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SolveEvent {
+    /// The owner credited for the solution.
+    pub owner: AccountOwner,
+    /// The timestamp of the solution.
+    pub timestamp: Timestamp,
+    /// The rank of the owner among the solvers of the puzzle, starting at 1.
+    pub rank: u32,
+}
+
+/// A solution credited to an owner on a scoring chain.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct CreditedSolution {
+    /// The timestamp of the solution.
+    pub timestamp: Timestamp,
+    /// The rank of the owner among the solvers of the puzzle, starting at 1.
+    pub rank: u32,
+    /// The points credited for the solution, including streak points.
+    pub points: u64,
+}
+
+/// The running score of an owner on a scoring chain.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct OwnerScore {
+    /// The total number of points.
+    pub points: u64,
+    /// The number of registered puzzles solved.
+    pub solved_puzzles: u32,
+}
+
+/// A named competition with its own time window and puzzle set.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Season {
+    /// The name of the season.
+    pub name: String,
+    /// The start of the season, included.
+    pub start: Timestamp,
+    /// The end of the season, excluded.
+    pub end: Timestamp,
+    /// The puzzles counting for the season.
+    pub puzzle_ids: Vec<DataBlobHash>,
+}
+
+impl Season {
+    /// Whether the season is running at the given time.
+    pub fn contains(&self, timestamp: Timestamp) -> bool {
+        self.start <= timestamp && timestamp < self.end
+    }
+}
+
+/// An ordered list of puzzles with prerequisites.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Campaign {
+    /// The name of the campaign.
+    pub name: String,
+    /// The puzzles of the campaign, each coming after its prerequisites.
+    pub puzzles: Vec<CampaignPuzzle>,
+}
+
+impl Campaign {
+    /// The prerequisites of a puzzle of the campaign.
+    pub fn prerequisites(&self, puzzle_id: DataBlobHash) -> &[DataBlobHash] {
+        self.puzzles
+            .iter()
+            .find(|puzzle| puzzle.puzzle_id == puzzle_id)
+            .map_or(&[], |puzzle| &puzzle.prerequisites)
+    }
+}
+
+/// A token reward paid for a solution.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RewardPayout {
+    /// The ID of the puzzle that was solved.
+    pub puzzle_id: DataBlobHash,
+    /// The account receiving the reward.
+    pub recipient: Account,
+    /// The amount paid.
+    pub amount: Amount,
+    /// The timestamp of the solution.
+    pub timestamp: Timestamp,
+}
+
+/// A solution commitment received by a scoring chain, before the solution is revealed.
+/// The commitment expires after `SOLUTION_COMMIT_TTL_MICROS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolutionCommit {
+    /// The ID of the puzzle that was solved.
+    pub puzzle_id: DataBlobHash,
+    /// The time when the scoring chain received the commitment. Revealed solutions are
+    /// credited at this time.
+    pub timestamp: Timestamp,
+    /// The number of owners credited for the puzzle when the commitment was received. The
+    /// revealed solution is ranked right after them.
+    pub credited_before: u32,
+}
+
+/// A bounty posted by a user for solving a puzzle, escrowed by a scoring chain.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Bounty {
+    /// The ID of the puzzle to solve.
+    pub puzzle_id: DataBlobHash,
+    /// The account that posted the bounty.
+    pub poster: Account,
+    /// The amount of the bounty.
+    pub amount: Amount,
+    /// The time after which the bounty may be reclaimed instead.
+    pub expiry: Timestamp,
+}
+
+/// A duel between two players of different user chains. Each chain keeps a copy of the
+/// duel and decides its outcome from the solutions it received, so that the outcome does
+/// not depend on the inbox of a single player.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Duel {
+    /// The ID of the duel.
+    pub id: DuelId,
+    /// The ID of the puzzle to solve.
+    pub puzzle_id: DataBlobHash,
+    /// The player that created the duel.
+    pub challenger: Account,
+    /// The player that was challenged.
+    pub opponent: Account,
+    /// The time after which solutions are no longer accepted.
+    pub deadline: Timestamp,
+    /// The metric deciding the winner, if any. Otherwise, the first valid solution wins.
+    pub metric: Option<QualityMetric>,
+    /// The status of the duel.
+    pub status: DuelStatus,
+    /// The first valid solution of the challenger.
+    pub challenger_solution: Option<DuelSolution>,
+    /// The first valid solution of the opponent.
+    pub opponent_solution: Option<DuelSolution>,
+    /// The outcome of the duel, once resolved.
+    pub outcome: Option<DuelOutcome>,
+}
+
+/// The status of a duel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum DuelStatus {
+    /// The opponent has not accepted the duel yet.
+    Pending,
+    /// The players may submit solutions.
+    Accepted,
+    /// The outcome of the duel is known.
+    Resolved,
+    /// The duel was not accepted before the deadline.
+    Expired,
+}
+
+/// A valid solution submitted to a duel.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct DuelSolution {
+    /// The initial state of the board solving the puzzle.
+    pub board: Board,
+    /// When the solution was submitted on this chain, or received from the other one.
+    pub timestamp: Timestamp,
+    /// The quality metrics of the solution.
+    pub metrics: QualityMetrics,
+}
+
+/// The results of the duels of a player.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct DuelRecord {
+    /// The number of duels won.
+    pub wins: u32,
+    /// The number of duels lost.
+    pub losses: u32,
+    /// The number of duels without a winner.
+    pub draws: u32,
+}
+
+/// An Immigration game between two players of different user chains. Each chain keeps a
+/// copy of the game and applies the turns of both players.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ImmigrationMatch {
+    /// The ID of the game.
+    pub id: GameId,
+    /// The player that created the game and plays first.
+    pub first_player: Account,
+    /// The player that plays second.
+    pub second_player: Account,
+    /// The state of the game.
+    pub game: ImmigrationGame,
+}
+
+/// A team of owners on a scoring chain.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Team {
+    /// The owner who created the team.
+    pub creator: AccountOwner,
+    /// How the score of the team is computed.
+    pub policy: TeamPolicy,
+    /// The members of the team, including the creator.
+    pub members: Vec<AccountOwner>,
+    /// The owners invited to join the team.
+    pub invitations: Vec<AccountOwner>,
+    /// The sum of the points of the members.
+    pub points: u64,
+    /// The number of distinct puzzles solved by at least one member.
+    pub solved_puzzles: u32,
 }
@@ -0,0 +1,88 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+/// How the score of a team is computed from the solutions of its members.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum TeamPolicy {
+    /// The number of distinct puzzles solved by at least one member.
+    #[default]
+    UnionOfPuzzles,
+    /// The sum of the points of the members.
+    SumOfPoints,
+}
+
+impl TeamPolicy {
+    /// The score of a team with the given points and number of distinct solved puzzles.
+    pub fn score(self, points: u64, solved_puzzles: u32) -> u64 {
+        match self {
+            TeamPolicy::UnionOfPuzzles => u64::from(solved_puzzles),
+            TeamPolicy::SumOfPoints => points,
+        }
+    }
+}
+
+/// A key of the team leaderboard index. Keys are serialized so that they are sorted by
+/// decreasing score, then by team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamKey {
+    /// The big-endian bytes of `u64::MAX - score`.
+    inverted_score: [u8; 8],
+    /// The name of the team with this score.
+    pub name: String,
+}
+
+impl TeamKey {
+    /// Creates the leaderboard key of a team with the given score.
+    pub fn new(name: String, score: u64) -> Self {
+        TeamKey {
+            inverted_score: (u64::MAX - score).to_be_bytes(),
+            name,
+        }
+    }
+
+    /// The score of the team.
+    pub fn score(&self) -> u64 {
+        u64::MAX - u64::from_be_bytes(self.inverted_score)
+    }
+}
+
+/// An entry of the team leaderboard.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct TeamEntry {
+    /// The rank of the team, starting at 1.
+    pub rank: u32,
+    /// The name of the team.
+    pub name: String,
+    /// The score of the team, according to its policy.
+    pub score: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_team_keys() {
+        assert_eq!(TeamPolicy::UnionOfPuzzles.score(40, 3), 3);
+        assert_eq!(TeamPolicy::SumOfPoints.score(40, 3), 40);
+        let keys = [
+            TeamKey::new("b".to_string(), 10),
+            TeamKey::new("a".to_string(), 10),
+            TeamKey::new("c".to_string(), 20),
+        ];
+        let mut serialized = keys
+            .iter()
+            .map(|key| bcs::to_bytes(key).unwrap())
+            .collect::<Vec<_>>();
+        serialized.sort();
+        let names = serialized
+            .iter()
+            .map(|bytes| bcs::from_bytes::<TeamKey>(bytes).unwrap().name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["c", "a", "b"]);
+        assert_eq!(keys[2].score(), 20);
+    }
+}
@@ -0,0 +1,404 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use linera_sdk::linera_base_types::{AccountOwner, DataBlobHash};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Sha3_256};
+
+use crate::{
+    game::{Board, Condition, InvalidPuzzle, Position, Puzzle},
+    quality::QualityRanking,
+};
+
+/// The prefix of the blobs of puzzle templates, distinguishing them from puzzle blobs.
+pub const TEMPLATE_BLOB_PREFIX: &[u8] = b"\0gol-template\0";
+
+/// The prefix of the blobs of puzzles or templates whose solvers are ranked by quality.
+pub const RANKED_BLOB_PREFIX: &[u8] = b"\0gol-ranked\0";
+
+/// A family of puzzles whose instances differ for each owner, so that a solution cannot
+/// be copied from one owner to another. Each instance is the base puzzle with its
+/// conditions translated, and possibly mirrored, according to the owner. Rectangles
+/// spanning the whole board along an axis, e.g. to count all live cells, are unchanged
+/// along that axis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PuzzleTemplate {
+    /// The instance with no translation and no mirroring.
+    pub puzzle: Puzzle,
+    /// The maximal translation of the conditions along the `x`-axis.
+    pub max_offset_x: u16,
+    /// The maximal translation of the conditions along the `y`-axis.
+    pub max_offset_y: u16,
+    /// Whether instances may be mirrored along each axis.
+    pub allow_mirroring: bool,
+}
+
+/// How the instance of a template for an owner is derived from the base puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateParameters {
+    /// The translation along the `x`-axis.
+    pub offset_x: u16,
+    /// The translation along the `y`-axis.
+    pub offset_y: u16,
+    /// Whether the translated positions are mirrored along the `x`-axis.
+    pub mirror_x: bool,
+    /// Whether the translated positions are mirrored along the `y`-axis.
+    pub mirror_y: bool,
+}
+
+/// A puzzle or template blob, with the quality ranking of the solvers of its puzzles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedPuzzle {
+    /// How the solvers are ranked by scoring chains.
+    pub ranking: QualityRanking,
+    /// The contents of the puzzle or template blob.
+    pub blob: Vec<u8>,
+}
+
+/// The contents of a puzzle blob.
+#[derive(Debug, Clone)]
+pub enum PuzzleBlob {
+    /// A puzzle, identical for every owner.
+    Puzzle(Puzzle),
+    /// A puzzle template, instantiated for each owner.
+    Template(PuzzleTemplate),
+    /// A puzzle or a template whose solvers are ranked by quality.
+    Ranked(QualityRanking, Box<PuzzleBlob>),
+}
+
+impl PuzzleTemplate {
+    /// Serializes the template into the contents of a blob.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bcs::Error> {
+        let mut bytes = TEMPLATE_BLOB_PREFIX.to_vec();
+        bcs::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Checks the base puzzle, and that the conditions still fit in the board with the
+    /// maximal translation.
+    pub fn validate(&self) -> Result<(), InvalidPuzzle> {
+        self.puzzle.validate()?;
+        let parameters = TemplateParameters {
+            offset_x: self.max_offset_x,
+            offset_y: self.max_offset_y,
+            mirror_x: false,
+            mirror_y: false,
+        };
+        parameters.puzzle(&self.puzzle).validate()
+    }
+
+    /// The parameters of the instance of the template for an owner, derived from the
+    /// SHA3-256 hash of the BCS serialization of `(template_id, owner)`.
+    pub fn parameters(
+        &self,
+        template_id: DataBlobHash,
+        owner: &AccountOwner,
+    ) -> TemplateParameters {
+        let bytes = bcs::to_bytes(&(template_id, owner)).expect("Serialization should not fail");
+        let seed = Sha3_256::digest(bytes);
+        let value = |index: usize, max: u16| {
+            let value = u32::from(u16::from_be_bytes([seed[index], seed[index + 1]]));
+            (value % (u32::from(max) + 1)) as u16
+        };
+        TemplateParameters {
+            offset_x: value(0, self.max_offset_x),
+            offset_y: value(2, self.max_offset_y),
+            mirror_x: self.allow_mirroring && seed[4] & 1 != 0,
+            mirror_y: self.allow_mirroring && seed[4] & 2 != 0,
+        }
+    }
+
+    /// The instance of the template for an owner.
+    pub fn instance(&self, template_id: DataBlobHash, owner: &AccountOwner) -> Puzzle {
+        self.parameters(template_id, owner).puzzle(&self.puzzle)
+    }
+}
+
+impl TemplateParameters {
+    /// Maps a position of the base puzzle to the instance, on a board of the given size.
+    pub fn position(&self, position: Position, size: u16) -> Position {
+        let x = position.x.saturating_add(self.offset_x);
+        let y = position.y.saturating_add(self.offset_y);
+        let mirror = |value: u16, is_mirrored: bool| {
+            if is_mirrored {
+                size.saturating_sub(1).saturating_sub(value)
+            } else {
+                value
+            }
+        };
+        Position {
+            x: mirror(x, self.mirror_x),
+            y: mirror(y, self.mirror_y),
+        }
+    }
+
+    /// Maps a condition of the base puzzle to the instance, on a board of the given size.
+    /// Ranges of rectangles covering the whole board are kept as they are.
+    pub fn condition(&self, condition: &Condition, size: u16) -> Condition {
+        match condition {
+            Condition::TestPosition { position, is_live } => Condition::TestPosition {
+                position: self.position(*position, size),
+                is_live: *is_live,
+            },
+            Condition::TestRectangle {
+                x_range,
+                y_range,
+                min_live_count,
+                max_live_count,
+            } => {
+                let map = |start: u16, end: u16, offset: u16, is_mirrored: bool| {
+                    if start == 0 && end >= size {
+                        return start..end;
+                    }
+                    let start = start.saturating_add(offset);
+                    let end = end.saturating_add(offset);
+                    if is_mirrored {
+                        size.saturating_sub(end)..size.saturating_sub(start)
+                    } else {
+                        start..end
+                    }
+                };
+                Condition::TestRectangle {
+                    x_range: map(x_range.start, x_range.end, self.offset_x, self.mirror_x),
+                    y_range: map(y_range.start, y_range.end, self.offset_y, self.mirror_y),
+                    min_live_count: *min_live_count,
+                    max_live_count: *max_live_count,
+                }
+            }
+        }
+    }
+
+    /// Maps the base puzzle to the instance.
+    pub fn puzzle(&self, puzzle: &Puzzle) -> Puzzle {
+        let conditions = |conditions: &[Condition]| {
+            conditions
+                .iter()
+                .map(|condition| self.condition(condition, puzzle.size))
+                .collect()
+        };
+        Puzzle {
+            initial_conditions: conditions(&puzzle.initial_conditions),
+            final_conditions: conditions(&puzzle.final_conditions),
+            ..puzzle.clone()
+        }
+    }
+
+    /// Maps a board of the base puzzle, e.g. a solution, to the instance, on a board of
+    /// the given size.
+    pub fn board(&self, board: &Board, size: u16) -> Board {
+        let live_cells = board
+            .live_positions()
+            .iter()
+            .map(|position| self.position(*position, size))
+            .collect();
+        Board::with_live_cells(size, live_cells)
+    }
+}
+
+impl RankedPuzzle {
+    /// Serializes the ranked puzzle into the contents of a blob.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bcs::Error> {
+        let mut bytes = RANKED_BLOB_PREFIX.to_vec();
+        bcs::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+}
+
+impl PuzzleBlob {
+    /// Deserializes the contents of a puzzle blob.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bcs::Error> {
+        let Some(bytes) = bytes.strip_prefix(RANKED_BLOB_PREFIX) else {
+            return Self::from_unranked_bytes(bytes);
+        };
+        let ranked_puzzle: RankedPuzzle = bcs::from_bytes(bytes)?;
+        let blob = Self::from_unranked_bytes(&ranked_puzzle.blob)?;
+        Ok(PuzzleBlob::Ranked(ranked_puzzle.ranking, Box::new(blob)))
+    }
+
+    /// Deserializes the contents of a puzzle or template blob.
+    fn from_unranked_bytes(bytes: &[u8]) -> Result<Self, bcs::Error> {
+        match bytes.strip_prefix(TEMPLATE_BLOB_PREFIX) {
+            Some(bytes) => Ok(PuzzleBlob::Template(bcs::from_bytes(bytes)?)),
+            None => Ok(PuzzleBlob::Puzzle(bcs::from_bytes(bytes)?)),
+        }
+    }
+
+    /// Checks the puzzle or the template.
+    pub fn validate(&self) -> Result<(), InvalidPuzzle> {
+        match self {
+            PuzzleBlob::Puzzle(puzzle) => puzzle.validate(),
+            PuzzleBlob::Template(template) => template.validate(),
+            PuzzleBlob::Ranked(_, blob) => blob.validate(),
+        }
+    }
+
+    /// The quality ranking of the solvers, if any.
+    pub fn ranking(&self) -> Option<&QualityRanking> {
+        match self {
+            PuzzleBlob::Ranked(ranking, _) => Some(ranking),
+            _ => None,
+        }
+    }
+
+    /// The puzzle shared by all owners, i.e. the base puzzle of a template. Its title,
+    /// difficulty and size are those of every instance.
+    pub fn into_base_puzzle(self) -> Puzzle {
+        match self {
+            PuzzleBlob::Puzzle(puzzle) => puzzle,
+            PuzzleBlob::Template(template) => template.puzzle,
+            PuzzleBlob::Ranked(_, blob) => blob.into_base_puzzle(),
+        }
+    }
+
+    /// The puzzle to be solved by an owner.
+    pub fn into_puzzle_for(self, puzzle_id: DataBlobHash, owner: &AccountOwner) -> Puzzle {
+        match self {
+            PuzzleBlob::Puzzle(puzzle) => puzzle,
+            PuzzleBlob::Template(template) => template.instance(puzzle_id, owner),
+            PuzzleBlob::Ranked(_, blob) => blob.into_puzzle_for(puzzle_id, owner),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::CryptoHash;
+
+    use super::*;
+    use crate::{game::Difficulty, quality::QualityMetric};
+
+    /// A glider moving towards the bottom-right corner, from the top-left corner.
+    fn glider_template() -> (PuzzleTemplate, Board) {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let board =
+            Board::with_live_cells(16, glider.iter().map(|&(x, y)| Position { x, y }).collect());
+        let target = board.clone().advance(4);
+        let puzzle = Puzzle {
+            title: "Glider".to_string(),
+            summary: "Move a glider".to_string(),
+            difficulty: Difficulty::Easy,
+            size: 16,
+            metadata: String::new(),
+            minimal_steps: 4,
+            maximal_steps: 4,
+            enforce_initial_conditions: true,
+            is_strict: false,
+            initial_conditions: vec![Condition::TestRectangle {
+                x_range: 0..3,
+                y_range: 0..3,
+                min_live_count: 5,
+                max_live_count: 5,
+            }],
+            final_conditions: target
+                .live_positions()
+                .iter()
+                .map(|&position| Condition::TestPosition {
+                    position,
+                    is_live: true,
+                })
+                .collect(),
+        };
+        let template = PuzzleTemplate {
+            puzzle,
+            max_offset_x: 10,
+            max_offset_y: 10,
+            allow_mirroring: true,
+        };
+        (template, board)
+    }
+
+    #[test]
+    fn test_template_instances() {
+        let (template, solution) = glider_template();
+        template.validate().unwrap();
+        let template_id = DataBlobHash(CryptoHash::test_hash("glider"));
+        let owners = (0..8)
+            .map(|index| AccountOwner::from(CryptoHash::test_hash(format!("owner{index}"))))
+            .collect::<Vec<_>>();
+        let mut instances = Vec::new();
+        for owner in &owners {
+            let parameters = template.parameters(template_id, owner);
+            assert!(parameters.offset_x <= 10 && parameters.offset_y <= 10);
+            assert_eq!(parameters, template.parameters(template_id, owner));
+            let puzzle = template.instance(template_id, owner);
+            puzzle.validate().unwrap();
+            let board = parameters.board(&solution, 16);
+            assert_eq!(puzzle.check_solution(&board), Ok(4));
+            instances.push((parameters, puzzle, board));
+        }
+        // Some owners get different instances, where the solutions of others fail.
+        let (parameters, puzzle, board) = &instances[0];
+        let other = instances
+            .iter()
+            .find(|(other_parameters, _, _)| other_parameters != parameters)
+            .expect("Owners should get different instances");
+        assert!(puzzle.check_solution(&other.2).is_err());
+        assert!(other.1.check_solution(board).is_err());
+
+        let bytes = template.to_bytes().unwrap();
+        let PuzzleBlob::Template(decoded) = PuzzleBlob::from_bytes(&bytes).unwrap() else {
+            panic!("Expected a template");
+        };
+        assert_eq!(decoded.max_offset_x, 10);
+        let bytes = bcs::to_bytes(&template.puzzle).unwrap();
+        assert!(matches!(
+            PuzzleBlob::from_bytes(&bytes).unwrap(),
+            PuzzleBlob::Puzzle(_)
+        ));
+    }
+
+    #[test]
+    fn test_ranked_blobs() {
+        let (template, _) = glider_template();
+        let ranking = QualityRanking {
+            metric: QualityMetric::LiveCells,
+            par: Some(5),
+        };
+        let ranked_puzzle = RankedPuzzle {
+            ranking: ranking.clone(),
+            blob: template.to_bytes().unwrap(),
+        };
+        let bytes = ranked_puzzle.to_bytes().unwrap();
+        let blob = PuzzleBlob::from_bytes(&bytes).unwrap();
+        assert_eq!(blob.ranking(), Some(&ranking));
+        blob.validate().unwrap();
+        let template_id = DataBlobHash(CryptoHash::test_hash("template"));
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let puzzle = blob.into_puzzle_for(template_id, &owner);
+        let instance = template.instance(template_id, &owner);
+        assert_eq!(
+            bcs::to_bytes(&puzzle).unwrap(),
+            bcs::to_bytes(&instance).unwrap()
+        );
+
+        // Rankings cannot be nested.
+        let nested_puzzle = RankedPuzzle {
+            ranking,
+            blob: bytes,
+        };
+        assert!(PuzzleBlob::from_bytes(&nested_puzzle.to_bytes().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_template_validation() {
+        let (mut template, _) = glider_template();
+        // After 4 steps, the glider reaches the column `x = 3`.
+        template.max_offset_x = 12;
+        template.validate().unwrap();
+        template.max_offset_x = 13;
+        assert!(template.validate().is_err());
+
+        // A rectangle counting the live cells of the whole board is not translated.
+        template.max_offset_x = 12;
+        template
+            .puzzle
+            .final_conditions
+            .push(Condition::TestRectangle {
+                x_range: 0..16,
+                y_range: 0..16,
+                min_live_count: 5,
+                max_live_count: 5,
+            });
+        template.validate().unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{ComplexObject, InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use crate::game::Difficulty;
+
+/// The maximal rating of a puzzle. Ratings start at 1.
+pub const MAX_RATING: u8 = 5;
+
+/// The difficulty levels, from the easiest to the hardest.
+const DIFFICULTIES: [Difficulty; 5] = [
+    Difficulty::Tutorial,
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Expert,
+];
+
+/// The vote of an owner on a puzzle they solved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, InputObject, SimpleObject)]
+#[graphql(input_name = "PuzzleVoteInput")]
+pub struct PuzzleVote {
+    /// How much the owner liked the puzzle, from 1 to `MAX_RATING`.
+    pub rating: u8,
+    /// The difficulty of the puzzle, according to the owner.
+    pub difficulty: Difficulty,
+}
+
+impl PuzzleVote {
+    /// Whether the rating is in range.
+    pub fn is_valid(&self) -> bool {
+        (1..=MAX_RATING).contains(&self.rating)
+    }
+}
+
+/// The aggregated votes on a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
+pub struct VoteSummary {
+    /// The number of votes.
+    pub count: u32,
+    /// The number of votes for each rating, starting at 1.
+    pub rating_histogram: Vec<u32>,
+    /// The number of votes for each difficulty, from `TUTORIAL` to `EXPERT`.
+    pub difficulty_histogram: Vec<u32>,
+}
+
+impl Default for VoteSummary {
+    fn default() -> Self {
+        VoteSummary {
+            count: 0,
+            rating_histogram: vec![0; MAX_RATING as usize],
+            difficulty_histogram: vec![0; DIFFICULTIES.len()],
+        }
+    }
+}
+
+impl VoteSummary {
+    /// Adds a valid vote.
+    pub fn add(&mut self, vote: &PuzzleVote) {
+        self.count += 1;
+        self.rating_histogram[usize::from(vote.rating - 1)] += 1;
+        let difficulty_index = DIFFICULTIES
+            .iter()
+            .position(|difficulty| *difficulty == vote.difficulty)
+            .expect("All difficulties are listed");
+        self.difficulty_histogram[difficulty_index] += 1;
+    }
+
+    /// The average rating, if there are votes.
+    pub fn average_rating(&self) -> Option<f64> {
+        let total = self
+            .rating_histogram
+            .iter()
+            .zip(1..)
+            .map(|(count, rating)| u64::from(*count) * rating)
+            .sum::<u64>();
+        (self.count > 0).then(|| total as f64 / f64::from(self.count))
+    }
+
+    /// The difficulty closest to the average of the votes, if there are votes.
+    pub fn community_difficulty(&self) -> Option<Difficulty> {
+        if self.count == 0 {
+            return None;
+        }
+        let total = self
+            .difficulty_histogram
+            .iter()
+            .zip(0..)
+            .map(|(count, index)| u64::from(*count) * index)
+            .sum::<u64>();
+        let count = u64::from(self.count);
+        let index = (2 * total + count) / (2 * count);
+        Some(DIFFICULTIES[index as usize])
+    }
+}
+
+#[ComplexObject]
+impl VoteSummary {
+    /// The average rating, if there are votes.
+    async fn average(&self) -> Option<f64> {
+        self.average_rating()
+    }
+
+    /// The difficulty closest to the average of the votes, if there are votes.
+    async fn community(&self) -> Option<Difficulty> {
+        self.community_difficulty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vote_summary() {
+        let mut summary = VoteSummary::default();
+        assert_eq!(summary.average_rating(), None);
+        assert_eq!(summary.community_difficulty(), None);
+        for (rating, difficulty) in [
+            (5, Difficulty::Easy),
+            (4, Difficulty::Medium),
+            (3, Difficulty::Medium),
+        ] {
+            let vote = PuzzleVote { rating, difficulty };
+            assert!(vote.is_valid());
+            summary.add(&vote);
+        }
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.rating_histogram, vec![0, 0, 1, 1, 1]);
+        assert_eq!(summary.difficulty_histogram, vec![0, 1, 2, 0, 0]);
+        assert_eq!(summary.average_rating(), Some(4.0));
+        assert_eq!(summary.community_difficulty(), Some(Difficulty::Medium));
+        assert!(!PuzzleVote {
+            rating: 0,
+            difficulty: Difficulty::Hard
+        }
+        .is_valid());
+    }
+}
@@ -137,14 +137,14 @@ export class LineraService {
 
     const query = {
       query: `
-        query ValidateSolution($board: BoardInput!, $puzzleId: String!) {
-          validateSolution(board: $board, puzzleId: $puzzleId) {
+        query ValidateSolution($board: BoardInput!, $puzzleId: String!, $owner: AccountOwner) {
+          validateSolution(board: $board, puzzleId: $puzzleId, owner: $owner) {
             isValidAfterSteps
             errorMessage
           }
         }
       `,
-      variables: { board, puzzleId },
+      variables: { board, puzzleId, owner: lineraAdapter.getAddress() },
     };
 
     const result = await lineraAdapter.queryApplication<any>(query);
@@ -164,14 +164,15 @@ export class LineraService {
   async submitSolution(puzzleId: string, board: LineraBoard): Promise<boolean> {
     await this.ensureInitialized();
     const scoringChainId = this.getWalletInfo().scoringChainId;
+    const scoringChainIds = scoringChainId ? [scoringChainId] : [];
     try {
       const mutation = {
         query: `
-          mutation SubmitSolution($puzzleId: String!, $board: BoardInput!, $scoringChainId: String) {
-            submitSolution(puzzleId: $puzzleId, board: $board, scoringChainId: $scoringChainId)
+          mutation SubmitSolution($puzzleId: String!, $board: BoardInput!, $scoringChainIds: [String!]!) {
+            submitSolution(puzzleId: $puzzleId, board: $board, scoringChainIds: $scoringChainIds)
           }
         `,
-        variables: { puzzleId, board, scoringChainId },
+        variables: { puzzleId, board, scoringChainIds },
       };
 
       const result = await lineraAdapter.queryApplication<any>(mutation);
@@ -193,8 +194,8 @@ export class LineraService {
     try {
       const query = {
         query: `
-          query GetPuzzle($puzzleId: String!) {
-            puzzle(puzzleId: $puzzleId) {
+          query GetPuzzle($puzzleId: String!, $owner: AccountOwner) {
+            puzzle(puzzleId: $puzzleId, owner: $owner) {
               title
               summary
               difficulty
@@ -208,7 +209,7 @@ export class LineraService {
             }
           }
         `,
-        variables: { puzzleId },
+        variables: { puzzleId, owner: lineraAdapter.getAddress() },
       };
 
       const result = await lineraAdapter.queryApplication<any>(query);